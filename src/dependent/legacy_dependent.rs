@@ -2,8 +2,9 @@
 
 use std::{
     cmp::Ordering,
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     hash::{BuildHasher, Hash},
+    iter, mem,
 };
 
 use indexmap::IndexSet;
@@ -33,12 +34,13 @@ use crate::{
 
 use crate::{
     ActiveSingularWeave, DeduplicatableContents, DeduplicatableWeave, DiscreteContentResult,
-    DiscreteContents, DiscreteWeave, IndependentContents, MetadataWeave, SemiIndependentWeave,
-    SortableWeave, Weave,
+    DiscreteContents, DiscreteWeave, HashableContents, IndependentContents, MetadataWeave,
+    SemiIndependentWeave, SortableWeave, SplitError, Weave,
     dependent::{
         DependentNode, DependentWeave as NewDependentWeave, add_node_identifiers,
         add_node_identifiers_rev, build_thread,
     },
+    duplicate_groups, node_depth, split_contents, subtree_size,
 };
 
 #[allow(unused_imports)]
@@ -294,9 +296,27 @@ where
     fn contains_active(&self, id: &K) -> bool {
         self.active == Some(*id)
     }
+    fn is_on_active_thread(&self, id: &K) -> bool {
+        let mut current = self.active;
+
+        while let Some(node) = current.and_then(|id| self.nodes.get(&id)) {
+            if node.id == *id {
+                return true;
+            }
+            current = node.from;
+        }
+
+        false
+    }
     fn contains_bookmark(&self, id: &K) -> bool {
         self.bookmarked.contains(id)
     }
+    fn count_active(&self) -> usize {
+        usize::from(self.active.is_some())
+    }
+    fn count_bookmarked(&self) -> usize {
+        self.bookmarked.len()
+    }
     fn get_node(&self, id: &K) -> Option<&DependentNode<K, T, S>> {
         self.nodes.get(id)
     }
@@ -324,6 +344,89 @@ where
 
         build_thread(&self.nodes, *id, output);
     }
+    fn active_thread_vec(&self) -> Vec<K> {
+        let mut output = Vec::new();
+
+        if let Some(active) = self.active {
+            build_thread(&self.nodes, active, &mut output);
+        }
+
+        output
+    }
+    fn thread_from_vec(&self, id: &K) -> Vec<K> {
+        let mut output = Vec::new();
+
+        build_thread(&self.nodes, *id, &mut output);
+
+        output
+    }
+    fn get_depth(&self, id: &K) -> Option<usize> {
+        node_depth(*id, self.nodes.len(), |id| {
+            self.nodes.get(id).map(|node| node.from)
+        })
+    }
+    fn subtree_size(&self, id: &K) -> Option<usize> {
+        subtree_size(*id, |id| {
+            self.nodes.get(id).map(|node| node.to.iter().copied())
+        })
+    }
+    fn ancestors(&self, id: &K) -> impl Iterator<Item = K> {
+        iter::successors(self.nodes.get(id).and_then(|node| node.from), |parent| {
+            self.nodes.get(parent).and_then(|node| node.from)
+        })
+    }
+    fn descendants(&self, id: &K) -> impl Iterator<Item = K> {
+        let mut stack: Vec<K> = self
+            .nodes
+            .get(id)
+            .map(|node| node.to.iter().rev().copied().collect())
+            .unwrap_or_default();
+
+        iter::from_fn(move || {
+            let descendant = stack.pop()?;
+
+            if let Some(node) = self.nodes.get(&descendant) {
+                stack.extend(node.to.iter().rev().copied());
+            }
+
+            Some(descendant)
+        })
+    }
+    fn leaves(&self) -> impl Iterator<Item = K> {
+        let mut stack: Vec<K> = self.roots.iter().rev().copied().collect();
+
+        iter::from_fn(move || {
+            loop {
+                let id = stack.pop()?;
+
+                if let Some(node) = self.nodes.get(&id) {
+                    if node.to.is_empty() {
+                        return Some(id);
+                    }
+
+                    stack.extend(node.to.iter().rev().copied());
+                }
+            }
+        })
+    }
+    fn get_all_threads(&self) -> impl Iterator<Item = Vec<K>> {
+        self.leaves()
+            .map(|leaf| iter::once(leaf).chain(self.ancestors(&leaf)).collect())
+    }
+    fn search_contents(&self, predicate: impl Fn(&T) -> bool) -> impl Iterator<Item = K> {
+        self.nodes
+            .values()
+            .filter(move |node| predicate(&node.contents))
+            .map(|node| node.id)
+    }
+    fn get_sibling_index(&self, id: &K) -> Option<(usize, usize)> {
+        let siblings = match self.nodes.get(id)?.from {
+            Some(parent) => &self.nodes.get(&parent)?.to,
+            None => &self.roots,
+        };
+
+        Some((siblings.get_index_of(id)?, siblings.len()))
+    }
     fn add_node(&mut self, node: DependentNode<K, T, S>) -> bool {
         if self.nodes.contains_key(&node.id)
             || !node.validate()
@@ -485,6 +588,27 @@ where
     fn sort_roots_by_id(&mut self, compare: impl FnMut(&K, &K) -> Ordering) {
         self.roots.sort_by(compare);
     }
+    fn move_child_to_index(&mut self, parent: &K, child: &K, index: usize) -> bool {
+        if let Some(node) = self.nodes.get_mut(parent)
+            && let Some(current) = node.to.get_index_of(child)
+        {
+            node.to.move_index(current, index.min(node.to.len() - 1));
+
+            true
+        } else {
+            false
+        }
+    }
+    fn move_root_to_index(&mut self, id: &K, index: usize) -> bool {
+        if let Some(current) = self.roots.get_index_of(id) {
+            self.roots
+                .move_index(current, index.min(self.roots.len() - 1));
+
+            true
+        } else {
+            false
+        }
+    }
     fn sort_bookmarks_by(
         &mut self,
         mut compare: impl FnMut(&DependentNode<K, T, S>, &DependentNode<K, T, S>) -> Ordering,
@@ -513,41 +637,108 @@ where
     T: DiscreteContents,
     S: BuildHasher + Default + Clone,
 {
-    fn split_node(&mut self, id: &K, at: usize, new_id: K) -> bool {
-        if self.nodes.contains_key(&new_id) || *id == new_id || !self.under_max_size() {
+    fn try_split_node(&mut self, id: &K, at: usize, new_id: K) -> Result<(), SplitError> {
+        let Some(node) = self.nodes.get(id) else {
+            return Err(SplitError::NodeNotFound);
+        };
+
+        if self.nodes.contains_key(&new_id) || *id == new_id {
+            return Err(SplitError::IdentifierInUse);
+        }
+        if !self.under_max_size() {
+            return Err(SplitError::CapacityExceeded);
+        }
+        if !node.contents.can_split_at(at) {
+            return Err(SplitError::InvalidIndex);
+        }
+
+        let mut node = self.nodes.remove(id).unwrap();
+
+        match node.contents.split(at) {
+            DiscreteContentResult::Two(left, right) => {
+                let left_node = DependentNode {
+                    id: node.id,
+                    from: node.from,
+                    to: IndexSet::from_iter([new_id]),
+                    active: node.active,
+                    bookmarked: node.bookmarked,
+                    contents: left,
+                };
+
+                node.from = Some(node.id);
+                node.id = new_id;
+                node.contents = right;
+                node.active = false;
+                node.bookmarked = false;
+
+                for child in node.to.iter() {
+                    let child = self.nodes.get_mut(child).unwrap();
+                    child.from = Some(node.id);
+                }
+
+                self.nodes.insert(left_node.id, left_node);
+                self.nodes.insert(node.id, node);
+
+                Ok(())
+            }
+            DiscreteContentResult::One(content) => {
+                node.contents = content;
+                self.nodes.insert(node.id, node);
+                Err(SplitError::SplitFailed)
+            }
+        }
+    }
+    fn split_node_multi(&mut self, id: &K, ats: &[usize], new_ids: &[K]) -> bool {
+        if ats.len() != new_ids.len()
+            || !ats.is_sorted_by(|a, b| a < b)
+            || new_ids
+                .iter()
+                .any(|new_id| new_id == id || self.nodes.contains_key(new_id))
+            || new_ids.iter().collect::<HashSet<_>>().len() != new_ids.len()
+            || (self.nodes.len() as u64 + new_ids.len() as u64) > (i32::MAX as u64)
+        {
             return false;
         }
 
         if let Some(mut node) = self.nodes.remove(id) {
-            match node.contents.split(at) {
-                DiscreteContentResult::Two(left, right) => {
-                    let left_node = DependentNode {
-                        id: node.id,
-                        from: node.from,
-                        to: IndexSet::from_iter([new_id]),
-                        active: node.active,
-                        bookmarked: node.bookmarked,
-                        contents: left,
-                    };
-
-                    node.from = Some(node.id);
-                    node.id = new_id;
-                    node.contents = right;
-                    node.active = false;
-                    node.bookmarked = false;
-
-                    for child in node.to.iter() {
+            match split_contents(node.contents, ats) {
+                Ok(pieces) => {
+                    let identifiers: Vec<K> =
+                        iter::once(node.id).chain(new_ids.iter().copied()).collect();
+                    let mut children = mem::take(&mut node.to);
+
+                    for child in children.iter() {
                         let child = self.nodes.get_mut(child).unwrap();
-                        child.from = Some(node.id);
+                        child.from = identifiers.last().copied();
                     }
 
-                    self.nodes.insert(left_node.id, left_node);
-                    self.nodes.insert(node.id, node);
+                    for (index, contents) in pieces.into_iter().enumerate() {
+                        let is_first = index == 0;
+
+                        self.nodes.insert(
+                            identifiers[index],
+                            DependentNode {
+                                id: identifiers[index],
+                                from: if is_first {
+                                    node.from
+                                } else {
+                                    Some(identifiers[index - 1])
+                                },
+                                to: match identifiers.get(index + 1) {
+                                    Some(next) => IndexSet::from_iter([*next]),
+                                    None => mem::take(&mut children),
+                                },
+                                active: is_first && node.active,
+                                bookmarked: is_first && node.bookmarked,
+                                contents,
+                            },
+                        );
+                    }
 
                     true
                 }
-                DiscreteContentResult::One(content) => {
-                    node.contents = content;
+                Err(contents) => {
+                    node.contents = contents;
                     self.nodes.insert(node.id, node);
                     false
                 }
@@ -617,6 +808,44 @@ where
             .get_mut(id)
             .map(|node| callback(&mut node.contents))
     }
+    fn with_node_mut(
+        &mut self,
+        id: &K,
+        callback: impl FnOnce(&mut DependentNode<K, T, S>),
+    ) -> bool {
+        let Some(node) = self.nodes.get_mut(id) else {
+            return false;
+        };
+        let (from, to, active, bookmarked) = (
+            node.from,
+            mem::take(&mut node.to),
+            node.active,
+            node.bookmarked,
+        );
+
+        callback(node);
+
+        let new_active = node.active;
+        node.id = *id;
+        node.from = from;
+        node.to = to;
+        node.active = active;
+
+        // The bookmark flag only needs to agree with the set of bookmarks.
+        if node.bookmarked != bookmarked {
+            if node.bookmarked {
+                self.bookmarked.insert(*id);
+            } else {
+                self.bookmarked.shift_remove(id);
+            }
+        }
+        // Writing the active flag directly could leave the active nodes without a single thread, so activity changes are made in place instead.
+        if new_active != active {
+            self.set_node_active_status_in_place(id, new_active);
+        }
+
+        true
+    }
 }
 
 impl<K, T, M, S> DeduplicatableWeave<K, DependentNode<K, T, S>, T> for DependentWeave<K, T, M, S>
@@ -636,6 +865,12 @@ where
             })
         })
     }
+    fn find_duplicate_groups(&self) -> impl Iterator<Item = Vec<K>>
+    where
+        T: HashableContents,
+    {
+        duplicate_groups(self.nodes.values().map(|node| (node.id, &node.contents)))
+    }
 }
 
 #[cfg(feature = "rkyv")]
@@ -676,6 +911,9 @@ where
     fn contains_bookmark(&self, id: &K::Archived) -> bool {
         self.bookmarked.contains(id)
     }
+    fn count_bookmarked(&self) -> usize {
+        self.bookmarked.len()
+    }
     fn get_node(&self, id: &K::Archived) -> Option<&ArchivedDependentNode<K, T, S>> {
         self.nodes.get(id)
     }
@@ -702,6 +940,43 @@ where
 
         build_thread_archived(&self.nodes, *id, output);
     }
+    fn get_depth(&self, id: &K::Archived) -> Option<usize> {
+        node_depth(*id, self.nodes.len(), |id| {
+            self.nodes.get(id).map(|node| node.from.as_ref().copied())
+        })
+    }
+    fn subtree_size(&self, id: &K::Archived) -> Option<usize> {
+        subtree_size(*id, |id| {
+            self.nodes.get(id).map(|node| node.to.iter().copied())
+        })
+    }
+    fn search_contents(
+        &self,
+        predicate: impl Fn(&T::Archived) -> bool,
+    ) -> impl Iterator<Item = K::Archived> {
+        self.nodes
+            .values()
+            .filter(move |node| predicate(&node.contents))
+            .map(|node| node.id)
+    }
+    fn leaves(&self) -> impl Iterator<Item = K::Archived> {
+        let mut stack: Vec<K::Archived> = self.roots.iter().copied().collect();
+        stack.reverse();
+
+        iter::from_fn(move || {
+            loop {
+                let id = stack.pop()?;
+
+                if let Some(node) = self.nodes.get(&id) {
+                    if node.to.is_empty() {
+                        return Some(id);
+                    }
+
+                    stack.extend(node.to.iter().collect::<Vec<_>>().into_iter().rev());
+                }
+            }
+        })
+    }
 }
 
 #[cfg(feature = "rkyv")]
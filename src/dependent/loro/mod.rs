@@ -21,8 +21,8 @@ use rkyv::{
 use stacksafe::stacksafe;
 
 use crate::{
-    ActiveSingularWeave, DeduplicatableContents, DeduplicatableWeave, IndependentContents,
-    SemiIndependentWeave, SortableWeave, Weave,
+    ActiveSingularWeave, DeduplicatableContents, DeduplicatableWeave, HashableContents,
+    IndependentContents, SemiIndependentWeave, SortableWeave, Weave,
     dependent::{DependentNode, DependentWeave},
 };
 
@@ -453,9 +453,18 @@ where
     fn contains_active(&self, id: &K) -> bool {
         self.weave.contains_active(id)
     }
+    fn is_on_active_thread(&self, id: &K) -> bool {
+        self.weave.is_on_active_thread(id)
+    }
     fn contains_bookmark(&self, id: &K) -> bool {
         self.weave.contains_bookmark(id)
     }
+    fn count_active(&self) -> usize {
+        self.weave.count_active()
+    }
+    fn count_bookmarked(&self) -> usize {
+        self.weave.count_bookmarked()
+    }
     fn get_node(&self, id: &K) -> Option<&DependentNode<K, T, S>> {
         self.weave.get_node(id)
     }
@@ -471,6 +480,36 @@ where
     fn get_thread_from(&mut self, id: &K, output: &mut Vec<K>) {
         self.weave.get_thread_from(id, output);
     }
+    fn active_thread_vec(&self) -> Vec<K> {
+        self.weave.active_thread_vec()
+    }
+    fn thread_from_vec(&self, id: &K) -> Vec<K> {
+        self.weave.thread_from_vec(id)
+    }
+    fn get_depth(&self, id: &K) -> Option<usize> {
+        self.weave.get_depth(id)
+    }
+    fn subtree_size(&self, id: &K) -> Option<usize> {
+        self.weave.subtree_size(id)
+    }
+    fn ancestors(&self, id: &K) -> impl Iterator<Item = K> {
+        self.weave.ancestors(id)
+    }
+    fn descendants(&self, id: &K) -> impl Iterator<Item = K> {
+        self.weave.descendants(id)
+    }
+    fn leaves(&self) -> impl Iterator<Item = K> {
+        self.weave.leaves()
+    }
+    fn get_all_threads(&self) -> impl Iterator<Item = Vec<K>> {
+        self.weave.get_all_threads()
+    }
+    fn search_contents(&self, predicate: impl Fn(&T) -> bool) -> impl Iterator<Item = K> {
+        self.weave.search_contents(predicate)
+    }
+    fn get_sibling_index(&self, id: &K) -> Option<(usize, usize)> {
+        self.weave.get_sibling_index(id)
+    }
     fn add_node(&mut self, node: DependentNode<K, T, S>) -> bool {
         let id = node.id;
         let from = node.from;
@@ -786,6 +825,42 @@ where
                 .unwrap();
         }
     }
+    fn move_child_to_index(&mut self, parent: &K, child: &K, index: usize) -> bool {
+        if self.weave.move_child_to_index(parent, child, index) {
+            let tree = self.doc.get_tree("tree");
+            let index = self
+                .weave
+                .get_node(parent)
+                .unwrap()
+                .to
+                .get_index_of(child)
+                .unwrap();
+
+            tree.mov_to(
+                self.mapping.get(child).copied().unwrap(),
+                Some(self.mapping.get(parent).copied().unwrap()),
+                index,
+            )
+            .unwrap();
+
+            true
+        } else {
+            false
+        }
+    }
+    fn move_root_to_index(&mut self, id: &K, index: usize) -> bool {
+        if self.weave.move_root_to_index(id, index) {
+            let tree = self.doc.get_tree("tree");
+            let index = self.weave.roots.get_index_of(id).unwrap();
+
+            tree.mov_to(self.mapping.get(id).copied().unwrap(), None, index)
+                .unwrap();
+
+            true
+        } else {
+            false
+        }
+    }
     fn sort_bookmarks_by(
         &mut self,
         cmp: impl FnMut(&DependentNode<K, T, S>, &DependentNode<K, T, S>) -> std::cmp::Ordering,
@@ -880,6 +955,46 @@ where
             output
         })
     }
+    fn with_node_mut(
+        &mut self,
+        id: &K,
+        callback: impl FnOnce(&mut DependentNode<K, T, S>),
+    ) -> bool {
+        let mut flags = None;
+
+        let found = self.weave.with_node_mut(id, |node| {
+            let (active, bookmarked) = (node.active, node.bookmarked);
+
+            callback(node);
+
+            flags = Some((node.active, node.bookmarked));
+            node.active = active;
+            node.bookmarked = bookmarked;
+
+            self.doc
+                .get_tree("tree")
+                .get_meta(self.mapping.get(id).copied().unwrap())
+                .unwrap()
+                .insert("contents", to_bytes(&node.contents).unwrap().into_vec())
+                .unwrap();
+        });
+
+        if let Some((active, bookmarked)) = flags {
+            if self.weave.contains_bookmark(id) != bookmarked {
+                self.set_node_bookmarked_status(id, bookmarked);
+            }
+            if self
+                .weave
+                .nodes
+                .get(id)
+                .is_some_and(|node| node.active != active)
+            {
+                self.set_node_active_status_in_place(id, active);
+            }
+        }
+
+        found
+    }
 }
 
 impl<K, T, M, S> DeduplicatableWeave<K, DependentNode<K, T, S>, T>
@@ -906,4 +1021,10 @@ where
     fn find_duplicates(&self, id: &K) -> impl Iterator<Item = K> {
         self.weave.find_duplicates(id)
     }
+    fn find_duplicate_groups(&self) -> impl Iterator<Item = Vec<K>>
+    where
+        T: HashableContents,
+    {
+        self.weave.find_duplicate_groups()
+    }
 }
@@ -2,9 +2,9 @@
 
 use std::{
     cmp::Ordering,
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     hash::{BuildHasher, Hash},
-    iter,
+    iter, mem,
 };
 
 #[allow(unused_imports)] // false positive warning
@@ -16,7 +16,9 @@ use stacksafe::stacksafe;
 use rkyv::{
     Archive, Deserialize, Serialize,
     collections::swiss_table::{ArchivedHashMap, ArchivedIndexSet},
+    de::Pool,
     option::ArchivedOption,
+    rancor::{self, Strategy},
 };
 
 #[cfg(feature = "wincode")]
@@ -33,12 +35,14 @@ use crate::{
 
 use crate::{
     ActiveSingularWeave, DeduplicatableContents, DeduplicatableWeave, DiscreteContentResult,
-    DiscreteContents, DiscreteWeave, IndependentContents, IntegratedNode, MetadataWeave, Node,
-    SemiIndependentWeave, SortableWeave, Weave,
+    DiscreteContents, DiscreteWeave, HashableContents, IndependentContents, IntegratedNode,
+    MetadataWeave, Node, NodeBuilder, SemiIndependentWeave, SortableWeave, SplitError,
+    TryReserveError, Weave,
     contract::{
         lacks_duplicates, matches_topological_sort, matches_topological_sort_rev,
         valid_ordered_nodes, valid_thread,
     },
+    duplicate_groups, node_depth, split_contents, subtree_size,
 };
 
 mod contracts;
@@ -120,6 +124,23 @@ where
     }
 }
 
+impl<K, T, S> From<NodeBuilder<K, T, S>> for DependentNode<K, T, S>
+where
+    K: Hash + Copy + Eq,
+    S: BuildHasher + Default + Clone,
+{
+    fn from(value: NodeBuilder<K, T, S>) -> Self {
+        Self {
+            id: value.id,
+            from: value.parents.first().copied(),
+            to: IndexSet::default(),
+            active: value.active,
+            bookmarked: value.bookmarked,
+            contents: value.contents,
+        }
+    }
+}
+
 impl<K, T, S> Node<K, T> for DependentNode<K, T, S>
 where
     K: Hash + Copy + Eq,
@@ -201,7 +222,12 @@ where
     K: Hash + Copy + Eq,
     S: BuildHasher + Default + Clone,
 {
+    /// Creates an empty weave with space for at least `capacity` nodes.
+    ///
+    /// The capacity is clamped to the maximum number of nodes the weave can contain.
     pub fn with_capacity(capacity: usize, metadata: M) -> Self {
+        let capacity = capacity.min(i32::MAX as usize);
+
         Self {
             nodes: HashMap::with_capacity_and_hasher(capacity, S::default()),
             roots: IndexSet::with_capacity_and_hasher(capacity, S::default()),
@@ -213,18 +239,414 @@ where
     pub fn capacity(&self) -> usize {
         self.nodes.capacity()
     }
+    /// Reserves space for at least `additional` more nodes.
+    ///
+    /// The reservation is clamped so that the weave's capacity does not exceed the maximum number of nodes it can contain.
     pub fn reserve(&mut self, additional: usize) {
+        let additional = additional.min((i32::MAX as usize).saturating_sub(self.nodes.len()));
+
         self.nodes.reserve(additional);
         self.roots
             .reserve(self.nodes.capacity().saturating_sub(self.roots.len()));
         self.bookmarked
             .reserve(self.nodes.capacity().saturating_sub(self.bookmarked.len()));
     }
+    /// Tries to reserve space for at least `additional` more nodes, returning an error instead of aborting if an allocation fails.
+    ///
+    /// The reservation is clamped in the same way as [`DependentWeave::reserve`]. If an error is returned, some collections may have already been grown.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let additional = additional.min((i32::MAX as usize).saturating_sub(self.nodes.len()));
+
+        self.nodes.try_reserve(additional)?;
+        self.roots
+            .try_reserve(self.nodes.capacity().saturating_sub(self.roots.len()))?;
+        self.bookmarked
+            .try_reserve(self.nodes.capacity().saturating_sub(self.bookmarked.len()))?;
+
+        Ok(())
+    }
     pub fn shrink_to(&mut self, min_capacity: usize) {
         self.nodes.shrink_to(min_capacity);
         self.roots.shrink_to(min_capacity);
         self.bookmarked.shrink_to(min_capacity);
     }
+    /// Inserts a batch of nodes into the weave, returning a list indicating which nodes were successfully inserted in the order they were specified in.
+    ///
+    /// Unlike calling [`Weave::add_node`] repeatedly, nodes may be specified before their parents; insertion of a node is deferred until its parent is present in the weave.
+    #[ensures(old(self.nodes.len()) + ret.iter().filter(|inserted| **inserted).count() == self.nodes.len())]
+    #[invariant(self.validate())]
+    pub fn add_nodes(
+        &mut self,
+        nodes: impl IntoIterator<Item = DependentNode<K, T, S>>,
+    ) -> Vec<bool> {
+        let mut nodes: Vec<_> = nodes.into_iter().map(Some).collect();
+        let mut results = vec![false; nodes.len()];
+        let mut waiting: HashMap<K, Vec<usize>, S> = HashMap::default();
+        let mut ready: Vec<usize> = (0..nodes.len()).rev().collect();
+
+        while let Some(index) = ready.pop() {
+            if let Some(parent) = nodes[index].as_ref().unwrap().from
+                && !self.nodes.contains_key(&parent)
+            {
+                waiting.entry(parent).or_default().push(index);
+                continue;
+            }
+
+            let node = nodes[index].take().unwrap();
+            let id = node.id;
+
+            if self.add_node(node) {
+                results[index] = true;
+
+                if let Some(waiters) = waiting.remove(&id) {
+                    ready.extend(waiters.into_iter().rev());
+                }
+            }
+        }
+
+        results
+    }
+    /// Removes every node for which `predicate` returns `false`.
+    ///
+    /// Nodes are removed using the same semantics as [`Weave::remove_node`], starting from the most deeply nested nodes; removing a node also removes any children which would otherwise be left without a parent, regardless of whether `predicate` returned `true` for them.
+    #[ensures(old(self.nodes.len()) >= self.nodes.len())]
+    #[invariant(self.validate())]
+    pub fn retain(&mut self, mut predicate: impl FnMut(&DependentNode<K, T, S>) -> bool) {
+        let mut identifiers = Vec::with_capacity(self.nodes.len());
+        self.get_ordered_node_identifiers(&mut identifiers);
+        identifiers.retain(|id| !predicate(self.nodes.get(id).unwrap()));
+
+        for id in identifiers.iter().rev() {
+            self.remove_node_unverified(id);
+        }
+    }
+    /// Removes every node which is not part of the active thread (see [`Weave::get_active_thread`]).
+    ///
+    /// Bookmarks on removed nodes are discarded. If the weave does not have an active thread, every node is removed.
+    #[ensures(old(self.active_thread_vec()) == self.active_thread_vec())]
+    #[ensures(self.nodes.len() == self.active_thread_vec().len())]
+    #[invariant(self.validate())]
+    pub fn prune_to_active(&mut self) {
+        let thread: HashSet<K, S> = self.active_thread_vec().into_iter().collect();
+
+        self.retain(|node| thread.contains(&node.id));
+    }
+    /// Removes all nodes from the weave while keeping its allocated capacity, leaving the weave's metadata untouched.
+    #[ensures(self.nodes.is_empty())]
+    #[ensures(old(self.capacity()) <= self.capacity())]
+    #[invariant(self.validate())]
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+        self.roots.clear();
+        self.active = None;
+        self.bookmarked.clear();
+    }
+    /// Deactivates the active node (if present), leaving the weave without an active thread.
+    #[ensures(self.active.is_none())]
+    #[ensures(old(self.nodes.len()) == self.nodes.len())]
+    #[invariant(self.validate())]
+    pub fn clear_active(&mut self) {
+        if let Some(node) = self.active.take().and_then(|id| self.nodes.get_mut(&id)) {
+            node.active = false;
+        }
+    }
+    /// Activates the leaf node reached by starting at the node with the specified identifier and repeatedly following each node's first child, returning `false` if the node could not be found.
+    ///
+    /// The resulting active thread passes through the specified node and ends at a node without any children.
+    #[ensures(ret == self.nodes.contains_key(id))]
+    #[ensures(!ret || self.active.and_then(|active| self.nodes.get(&active)).is_some_and(|node| node.to.is_empty()))]
+    #[invariant(self.validate())]
+    pub fn activate_to_leaf(&mut self, id: &K) -> bool {
+        if !self.nodes.contains_key(id) {
+            return false;
+        }
+
+        let mut current = *id;
+
+        while let Some(child) = self
+            .nodes
+            .get(&current)
+            .and_then(|node| node.to.first())
+            .copied()
+        {
+            current = child;
+        }
+
+        self.set_node_active_status_in_place(&current, true)
+    }
+    /// Bookmarks every node for which `predicate` returns `true`, returning the number of nodes which were not previously bookmarked.
+    ///
+    /// Newly bookmarked nodes are appended to the list of bookmarks in an unspecified order.
+    #[ensures(self.nodes.values().all(|node| !predicate(node) || node.bookmarked))]
+    #[ensures(old(self.bookmarked.len()) + ret == self.bookmarked.len())]
+    #[invariant(self.validate())]
+    pub fn bookmark_matching(
+        &mut self,
+        predicate: impl Fn(&DependentNode<K, T, S>) -> bool,
+    ) -> usize {
+        let mut count = 0;
+
+        for node in self.nodes.values_mut() {
+            if !node.bookmarked && predicate(node) {
+                node.bookmarked = true;
+                self.bookmarked.insert(node.id);
+                count += 1;
+            }
+        }
+
+        count
+    }
+    /// Removes the bookmark from every node for which `predicate` returns `true`, returning the number of nodes which were previously bookmarked.
+    #[ensures(self.nodes.values().all(|node| !predicate(node) || !node.bookmarked))]
+    #[ensures(old(self.bookmarked.len()) - ret == self.bookmarked.len())]
+    #[invariant(self.validate())]
+    pub fn unbookmark_matching(
+        &mut self,
+        predicate: impl Fn(&DependentNode<K, T, S>) -> bool,
+    ) -> usize {
+        let mut count = 0;
+
+        for node in self.nodes.values_mut() {
+            if node.bookmarked && predicate(node) {
+                node.bookmarked = false;
+                self.bookmarked.shift_remove(&node.id);
+                count += 1;
+            }
+        }
+
+        count
+    }
+    /// Returns the identifier of the deepest node which is both an ancestor of (or equal to) `a` and an ancestor of (or equal to) `b`.
+    ///
+    /// Returns `None` if either node does not exist or if the nodes do not share any ancestors.
+    #[ensures(ret.is_none() || self.nodes.contains_key(&ret.unwrap()))]
+    pub fn lowest_common_ancestor(&self, a: &K, b: &K) -> Option<K> {
+        if !self.nodes.contains_key(a) || !self.nodes.contains_key(b) {
+            return None;
+        }
+
+        let a_ancestors: HashSet<K, S> = iter::once(*a).chain(self.ancestors(a)).collect();
+
+        iter::once(*b)
+            .chain(self.ancestors(b))
+            .find(|id| a_ancestors.contains(id))
+    }
+    /// Returns the identifier of the sibling immediately after the specified node, in the order of its parent's children (or the order of root nodes if the node is a root node).
+    ///
+    /// Returns `None` if the node does not exist or if it is the last of its siblings.
+    pub fn next_sibling(&self, id: &K) -> Option<K> {
+        let siblings = self.sibling_set(id)?;
+
+        siblings.get_index(siblings.get_index_of(id)? + 1).copied()
+    }
+    /// Returns the identifier of the sibling immediately before the specified node, in the order of its parent's children (or the order of root nodes if the node is a root node).
+    ///
+    /// Returns `None` if the node does not exist or if it is the first of its siblings.
+    pub fn prev_sibling(&self, id: &K) -> Option<K> {
+        let siblings = self.sibling_set(id)?;
+
+        siblings
+            .get_index(siblings.get_index_of(id)?.checked_sub(1)?)
+            .copied()
+    }
+    fn sibling_set(&self, id: &K) -> Option<&IndexSet<K, S>> {
+        let node = self.nodes.get(id)?;
+
+        match node.from {
+            Some(parent) => self.nodes.get(&parent).map(|parent| &parent.to),
+            None => Some(&self.roots),
+        }
+    }
+    /// Moves a node along with all of its descendants so that it becomes a child of `new_parent`, or a root node if `new_parent` is `None`.
+    ///
+    /// Returns `false` if either node does not exist or if `new_parent` is the node itself or one of its descendants.
+    #[ensures(!ret || self.nodes.get(id).unwrap().from == new_parent)]
+    #[ensures(ret || old(self.nodes.get(id).and_then(|node| node.from)) == self.nodes.get(id).and_then(|node| node.from))]
+    #[ensures(old(self.nodes.len()) == self.nodes.len())]
+    #[ensures(old(self.active) == self.active)]
+    #[invariant(self.validate())]
+    pub fn move_node(&mut self, id: &K, new_parent: Option<K>) -> bool {
+        let Some(old_parent) = self.nodes.get(id).map(|node| node.from) else {
+            return false;
+        };
+
+        if let Some(new_parent) = new_parent
+            && (new_parent == *id
+                || !self.nodes.contains_key(&new_parent)
+                || self.is_ancestor_of(id, &new_parent))
+        {
+            return false;
+        }
+
+        if old_parent == new_parent {
+            return true;
+        }
+
+        match old_parent.and_then(|parent| self.nodes.get_mut(&parent)) {
+            Some(parent) => {
+                parent.to.shift_remove(id);
+            }
+            None => {
+                self.roots.shift_remove(id);
+            }
+        }
+
+        match new_parent.and_then(|parent| self.nodes.get_mut(&parent)) {
+            Some(parent) => {
+                parent.to.insert(*id);
+            }
+            None => {
+                self.roots.insert(*id);
+            }
+        }
+
+        self.nodes.get_mut(id).unwrap().from = new_parent;
+
+        true
+    }
+    /// Merges children of the node with the specified identifier whose contents are duplicates of each other (see [`DeduplicatableContents::is_duplicate_of`]), returning the number of nodes which were removed.
+    ///
+    /// When two children are duplicates, the later child's children are moved onto the earlier child and the later child is removed. The surviving node is bookmarked if either node was bookmarked, and becomes the active node if the removed node was active. Descendants of the node's children are not deduplicated.
+    #[ensures(old(self.nodes.len()) == self.nodes.len() + ret)]
+    #[ensures(old(self.active.is_some()) == self.active.is_some())]
+    #[invariant(self.validate())]
+    pub fn merge_duplicate_children(&mut self, parent: &K) -> usize
+    where
+        T: DeduplicatableContents,
+    {
+        let Some(children) = self
+            .nodes
+            .get(parent)
+            .map(|node| node.to.iter().copied().collect::<Vec<_>>())
+        else {
+            return 0;
+        };
+
+        let mut survivors: Vec<K> = Vec::with_capacity(children.len());
+        let mut removed = 0;
+
+        for child in children {
+            let contents = &self.nodes.get(&child).unwrap().contents;
+
+            let Some(survivor) = survivors.iter().copied().find(|survivor| {
+                self.nodes
+                    .get(survivor)
+                    .unwrap()
+                    .contents
+                    .is_duplicate_of(contents)
+            }) else {
+                survivors.push(child);
+                continue;
+            };
+
+            let node = self.nodes.remove(&child).unwrap();
+
+            for grandchild in &node.to {
+                self.nodes.get_mut(grandchild).unwrap().from = Some(survivor);
+            }
+
+            self.nodes.get_mut(parent).unwrap().to.shift_remove(&child);
+
+            let survivor_node = self.nodes.get_mut(&survivor).unwrap();
+            survivor_node.to.extend(node.to);
+
+            if self.bookmarked.shift_remove(&child) {
+                survivor_node.bookmarked = true;
+                self.bookmarked.insert(survivor);
+            }
+
+            if node.active {
+                survivor_node.active = true;
+                self.active = Some(survivor);
+            }
+
+            removed += 1;
+        }
+
+        removed
+    }
+    /// Converts the weave into a parent-pointer array, returning the identifier and contents of each node in topological order alongside the index of each node's parent within that order (or `-1` for root nodes).
+    ///
+    /// The active and bookmarked status of nodes is not included.
+    #[ensures(ret.0.len() == self.nodes.len() && ret.1.len() == self.nodes.len())]
+    #[ensures(ret.1.iter().enumerate().all(|(index, parent)| *parent < index as i32))]
+    pub fn to_parent_pointer(&self) -> (Vec<(K, T)>, Vec<i32>)
+    where
+        T: Clone,
+    {
+        let mut identifiers = Vec::with_capacity(self.nodes.len());
+
+        for root in &self.roots {
+            add_node_identifiers(&self.nodes, *root, &mut identifiers);
+        }
+
+        let indices: HashMap<K, i32, S> = identifiers
+            .iter()
+            .enumerate()
+            .map(|(index, id)| (*id, index as i32))
+            .collect();
+
+        identifiers
+            .into_iter()
+            .map(|id| {
+                let node = self.nodes.get(&id).unwrap();
+
+                (
+                    (id, node.contents.clone()),
+                    node.from.map(|parent| indices[&parent]).unwrap_or(-1),
+                )
+            })
+            .unzip()
+    }
+    /// Reconstructs a weave from a parent-pointer array created by [`DependentWeave::to_parent_pointer`].
+    ///
+    /// Nodes are inserted directly rather than through [`Weave::add_node`], so large weaves can be rebuilt without validating the weave after every insertion. All nodes in the resulting weave are inactive and unbookmarked. Returns `None` if the arrays differ in length, if any parent index does not refer to an earlier node, or if any identifier is duplicated.
+    pub fn from_parent_pointer(nodes: Vec<(K, T)>, parents: &[i32], metadata: M) -> Option<Self> {
+        if nodes.len() != parents.len() {
+            return None;
+        }
+
+        let mut weave = Self::with_capacity(nodes.len(), metadata);
+        let mut identifiers = Vec::with_capacity(nodes.len());
+
+        for (index, ((id, contents), parent)) in nodes.into_iter().zip(parents).enumerate() {
+            let from = match *parent {
+                -1 => None,
+                parent if parent >= 0 && (parent as usize) < index => {
+                    Some(identifiers[parent as usize])
+                }
+                _ => return None,
+            };
+
+            if weave.nodes.contains_key(&id) || !weave.under_max_size() {
+                return None;
+            }
+
+            match from {
+                Some(parent) => {
+                    weave.nodes.get_mut(&parent).unwrap().to.insert(id);
+                }
+                None => {
+                    weave.roots.insert(id);
+                }
+            }
+
+            weave.nodes.insert(
+                id,
+                DependentNode {
+                    id,
+                    from,
+                    to: IndexSet::default(),
+                    active: false,
+                    bookmarked: false,
+                    contents,
+                },
+            );
+            identifiers.push(id);
+        }
+
+        Some(weave)
+    }
     fn siblings<'a>(
         &'a self,
         node: &'a DependentNode<K, T, S>,
@@ -248,60 +670,68 @@ where
         }
     }
     #[ensures(!self.nodes.contains_key(id))]
-    #[stacksafe]
     fn remove_node_unverified(&mut self, id: &K) -> Option<DependentNode<K, T, S>> {
-        if let Some(node) = self.nodes.remove(id) {
-            self.roots.shift_remove(id);
-            self.bookmarked.shift_remove(id);
-            for child in node.to.iter() {
-                self.remove_node_unverified(child);
-            }
-            if node.active {
-                self.active = node.from;
-                if let Some(parent) = node.from.and_then(|id| self.nodes.get_mut(&id)) {
-                    parent.active = true;
-                } else {
-                    self.active = None;
-                }
-            }
-            if let Some(parent) = node.from.and_then(|id| self.nodes.get_mut(&id)) {
-                parent.to.shift_remove(id);
+        let node = self.detach_node(id)?;
+        let mut worklist: Vec<K> = node.to.iter().copied().collect();
+
+        while let Some(child) = worklist.pop() {
+            if let Some(child) = self.remove_descendant(&child) {
+                worklist.extend(child.to.iter().copied());
             }
-            Some(node)
-        } else {
-            None
         }
+
+        Some(node)
     }
     #[ensures(!self.nodes.contains_key(id))]
-    #[stacksafe]
     fn remove_node_unverified_tracked(
         &mut self,
         id: &K,
         callback: &mut impl FnMut(DependentNode<K, T, S>),
     ) -> bool {
-        if let Some(node) = self.nodes.remove(id) {
-            self.roots.shift_remove(id);
-            self.bookmarked.shift_remove(id);
-            for child in node.to.iter() {
-                self.remove_node_unverified_tracked(child, callback);
-            }
-            if node.active {
-                self.active = node.from;
-                if let Some(parent) = node.from.and_then(|id| self.nodes.get_mut(&id)) {
-                    parent.active = true;
-                } else {
-                    self.active = None;
+        if let Some(node) = self.detach_node(id) {
+            let mut worklist: Vec<K> = node.to.iter().copied().collect();
+            let mut removed = vec![node];
+
+            while let Some(child) = worklist.pop() {
+                if let Some(child) = self.remove_descendant(&child) {
+                    worklist.extend(child.to.iter().copied());
+                    removed.push(child);
                 }
             }
-            if let Some(parent) = node.from.and_then(|id| self.nodes.get_mut(&id)) {
-                parent.to.shift_remove(id);
+
+            for node in removed.into_iter().rev() {
+                callback(node);
             }
-            callback(node);
+
             true
         } else {
             false
         }
     }
+    fn detach_node(&mut self, id: &K) -> Option<DependentNode<K, T, S>> {
+        let node = self.nodes.remove(id)?;
+        self.roots.shift_remove(id);
+        self.bookmarked.shift_remove(id);
+        if let Some(parent) = node.from.and_then(|id| self.nodes.get_mut(&id)) {
+            parent.to.shift_remove(id);
+            if node.active {
+                parent.active = true;
+                self.active = node.from;
+            }
+        } else if node.active {
+            self.active = None;
+        }
+        Some(node)
+    }
+    fn remove_descendant(&mut self, id: &K) -> Option<DependentNode<K, T, S>> {
+        let node = self.nodes.remove(id)?;
+        self.roots.shift_remove(id);
+        self.bookmarked.shift_remove(id);
+        if node.active {
+            self.active = None;
+        }
+        Some(node)
+    }
 }
 
 impl<K, T, M, S> Weave<K, DependentNode<K, T, S>, T> for DependentWeave<K, T, M, S>
@@ -334,9 +764,27 @@ where
     fn contains_active(&self, id: &K) -> bool {
         self.active == Some(*id)
     }
+    fn is_on_active_thread(&self, id: &K) -> bool {
+        let mut current = self.active;
+
+        while let Some(node) = current.and_then(|id| self.nodes.get(&id)) {
+            if node.id == *id {
+                return true;
+            }
+            current = node.from;
+        }
+
+        false
+    }
     fn contains_bookmark(&self, id: &K) -> bool {
         self.bookmarked.contains(id)
     }
+    fn count_active(&self) -> usize {
+        usize::from(self.active.is_some())
+    }
+    fn count_bookmarked(&self) -> usize {
+        self.bookmarked.len()
+    }
     fn get_node(&self, id: &K) -> Option<&DependentNode<K, T, S>> {
         self.nodes.get(id)
     }
@@ -373,6 +821,91 @@ where
 
         build_thread(&self.nodes, *id, output);
     }
+    #[ensures(ret.is_empty() == self.active.is_none())]
+    #[ensures(lacks_duplicates(&ret))]
+    #[ensures(valid_thread(&self.nodes, &ret))]
+    fn active_thread_vec(&self) -> Vec<K> {
+        let mut output = Vec::new();
+
+        if let Some(active) = self.active {
+            build_thread(&self.nodes, active, &mut output);
+        }
+
+        output
+    }
+    #[ensures(lacks_duplicates(&ret))]
+    #[ensures(valid_thread(&self.nodes, &ret))]
+    fn thread_from_vec(&self, id: &K) -> Vec<K> {
+        let mut output = Vec::new();
+
+        build_thread(&self.nodes, *id, &mut output);
+
+        output
+    }
+    fn get_depth(&self, id: &K) -> Option<usize> {
+        node_depth(*id, self.nodes.len(), |id| {
+            self.nodes.get(id).map(|node| node.from)
+        })
+    }
+    fn subtree_size(&self, id: &K) -> Option<usize> {
+        subtree_size(*id, |id| {
+            self.nodes.get(id).map(|node| node.to.iter().copied())
+        })
+    }
+    fn ancestors(&self, id: &K) -> impl Iterator<Item = K> {
+        iter::successors(self.nodes.get(id).and_then(|node| node.from), |parent| {
+            self.nodes.get(parent).and_then(|node| node.from)
+        })
+    }
+    fn descendants(&self, id: &K) -> impl Iterator<Item = K> {
+        let mut stack: Vec<K> = self
+            .nodes
+            .get(id)
+            .map(|node| node.to.iter().rev().copied().collect())
+            .unwrap_or_default();
+
+        iter::from_fn(move || {
+            let descendant = stack.pop()?;
+
+            if let Some(node) = self.nodes.get(&descendant) {
+                stack.extend(node.to.iter().rev().copied());
+            }
+
+            Some(descendant)
+        })
+    }
+    fn leaves(&self) -> impl Iterator<Item = K> {
+        let mut stack: Vec<K> = self.roots.iter().rev().copied().collect();
+
+        iter::from_fn(move || {
+            loop {
+                let id = stack.pop()?;
+
+                if let Some(node) = self.nodes.get(&id) {
+                    if node.to.is_empty() {
+                        return Some(id);
+                    }
+
+                    stack.extend(node.to.iter().rev().copied());
+                }
+            }
+        })
+    }
+    fn get_all_threads(&self) -> impl Iterator<Item = Vec<K>> {
+        self.leaves()
+            .map(|leaf| iter::once(leaf).chain(self.ancestors(&leaf)).collect())
+    }
+    fn search_contents(&self, predicate: impl Fn(&T) -> bool) -> impl Iterator<Item = K> {
+        self.nodes
+            .values()
+            .filter(move |node| predicate(&node.contents))
+            .map(|node| node.id)
+    }
+    fn get_sibling_index(&self, id: &K) -> Option<(usize, usize)> {
+        let siblings = self.sibling_set(id)?;
+
+        Some((siblings.get_index_of(id)?, siblings.len()))
+    }
     #[ensures(!ret || old(self.nodes.len()) + 1 == self.nodes.len())]
     #[ensures(!ret || old(!self.nodes.contains_key(&node.id)))]
     #[ensures(!ret || self.nodes.contains_key(&old(node.id)))]
@@ -584,6 +1117,34 @@ where
     fn sort_roots_by_id(&mut self, compare: impl FnMut(&K, &K) -> Ordering) {
         self.roots.sort_by(compare);
     }
+    #[ensures(old(self.nodes.len()) == self.nodes.len())]
+    #[ensures(old(self.nodes.get(parent).map(|node| node.to.len())) == self.nodes.get(parent).map(|node| node.to.len()))]
+    #[invariant(self.validate())]
+    fn move_child_to_index(&mut self, parent: &K, child: &K, index: usize) -> bool {
+        if let Some(node) = self.nodes.get_mut(parent)
+            && let Some(current) = node.to.get_index_of(child)
+        {
+            node.to.move_index(current, index.min(node.to.len() - 1));
+
+            true
+        } else {
+            false
+        }
+    }
+    #[ensures(old(self.nodes.len()) == self.nodes.len())]
+    #[ensures(old(self.roots.len()) == self.roots.len())]
+    #[ensures(ret == self.roots.contains(id))]
+    #[invariant(self.validate())]
+    fn move_root_to_index(&mut self, id: &K, index: usize) -> bool {
+        if let Some(current) = self.roots.get_index_of(id) {
+            self.roots
+                .move_index(current, index.min(self.roots.len() - 1));
+
+            true
+        } else {
+            false
+        }
+    }
     #[ensures(old(self.bookmarked.len()) == self.bookmarked.len())]
     #[invariant(self.validate())]
     fn sort_bookmarks_by(
@@ -616,49 +1177,123 @@ where
     T: DiscreteContents,
     S: BuildHasher + Default + Clone,
 {
-    #[ensures(!ret || old(self.nodes.len()) + 1 == self.nodes.len())]
+    #[ensures(ret.is_err() || old(self.nodes.len()) + 1 == self.nodes.len())]
+    #[ensures(ret.is_err() || self.nodes.contains_key(id))]
+    #[ensures(ret.is_err() || self.nodes.contains_key(&new_id))]
+    #[ensures(ret.is_err() || old(!self.nodes.contains_key(&new_id)))]
+    #[ensures(ret.is_ok() || old(self.nodes.len()) == self.nodes.len())]
+    #[ensures(ret.is_ok() || old(self.active) == self.active)]
+    #[ensures(ret.is_ok() || old(self.bookmarked.clone()) == self.bookmarked)]
+    #[invariant(self.validate())]
+    fn try_split_node(&mut self, id: &K, at: usize, new_id: K) -> Result<(), SplitError> {
+        let Some(node) = self.nodes.get(id) else {
+            return Err(SplitError::NodeNotFound);
+        };
+
+        if self.nodes.contains_key(&new_id) || *id == new_id {
+            return Err(SplitError::IdentifierInUse);
+        }
+        if !self.under_max_size() {
+            return Err(SplitError::CapacityExceeded);
+        }
+        if !node.contents.can_split_at(at) {
+            return Err(SplitError::InvalidIndex);
+        }
+
+        let mut node = self.nodes.remove(id).unwrap();
+
+        match node.contents.split(at) {
+            DiscreteContentResult::Two(left, right) => {
+                let left_node = DependentNode {
+                    id: node.id,
+                    from: node.from,
+                    to: IndexSet::from_iter([new_id]),
+                    active: node.active,
+                    bookmarked: node.bookmarked,
+                    contents: left,
+                };
+
+                node.from = Some(node.id);
+                node.id = new_id;
+                node.contents = right;
+                node.active = false;
+                node.bookmarked = false;
+
+                for child in node.to.iter() {
+                    let child = self.nodes.get_mut(child).unwrap();
+                    child.from = Some(node.id);
+                }
+
+                self.nodes.insert(left_node.id, left_node);
+                self.nodes.insert(node.id, node);
+
+                Ok(())
+            }
+            DiscreteContentResult::One(content) => {
+                node.contents = content;
+                self.nodes.insert(node.id, node);
+                Err(SplitError::SplitFailed)
+            }
+        }
+    }
+    #[ensures(!ret || old(self.nodes.len()) + new_ids.len() == self.nodes.len())]
     #[ensures(!ret || self.nodes.contains_key(id))]
-    #[ensures(!ret || self.nodes.contains_key(&new_id))]
-    #[ensures(!ret || old(!self.nodes.contains_key(&new_id)))]
+    #[ensures(!ret || new_ids.iter().all(|new_id| self.nodes.contains_key(new_id)))]
     #[ensures(ret || old(self.nodes.len()) == self.nodes.len())]
     #[ensures(ret || old(self.active) == self.active)]
     #[ensures(ret || old(self.bookmarked.clone()) == self.bookmarked)]
     #[invariant(self.validate())]
-    fn split_node(&mut self, id: &K, at: usize, new_id: K) -> bool {
-        if self.nodes.contains_key(&new_id) || *id == new_id || !self.under_max_size() {
+    fn split_node_multi(&mut self, id: &K, ats: &[usize], new_ids: &[K]) -> bool {
+        if ats.len() != new_ids.len()
+            || !ats.is_sorted_by(|a, b| a < b)
+            || new_ids
+                .iter()
+                .any(|new_id| new_id == id || self.nodes.contains_key(new_id))
+            || new_ids.iter().collect::<HashSet<_>>().len() != new_ids.len()
+            || (self.nodes.len() as u64 + new_ids.len() as u64) > (i32::MAX as u64)
+        {
             return false;
         }
 
         if let Some(mut node) = self.nodes.remove(id) {
-            match node.contents.split(at) {
-                DiscreteContentResult::Two(left, right) => {
-                    let left_node = DependentNode {
-                        id: node.id,
-                        from: node.from,
-                        to: IndexSet::from_iter([new_id]),
-                        active: node.active,
-                        bookmarked: node.bookmarked,
-                        contents: left,
-                    };
-
-                    node.from = Some(node.id);
-                    node.id = new_id;
-                    node.contents = right;
-                    node.active = false;
-                    node.bookmarked = false;
-
-                    for child in node.to.iter() {
+            match split_contents(node.contents, ats) {
+                Ok(pieces) => {
+                    let identifiers: Vec<K> =
+                        iter::once(node.id).chain(new_ids.iter().copied()).collect();
+                    let mut children = mem::take(&mut node.to);
+
+                    for child in children.iter() {
                         let child = self.nodes.get_mut(child).unwrap();
-                        child.from = Some(node.id);
+                        child.from = identifiers.last().copied();
                     }
 
-                    self.nodes.insert(left_node.id, left_node);
-                    self.nodes.insert(node.id, node);
+                    for (index, contents) in pieces.into_iter().enumerate() {
+                        let is_first = index == 0;
+
+                        self.nodes.insert(
+                            identifiers[index],
+                            DependentNode {
+                                id: identifiers[index],
+                                from: if is_first {
+                                    node.from
+                                } else {
+                                    Some(identifiers[index - 1])
+                                },
+                                to: match identifiers.get(index + 1) {
+                                    Some(next) => IndexSet::from_iter([*next]),
+                                    None => mem::take(&mut children),
+                                },
+                                active: is_first && node.active,
+                                bookmarked: is_first && node.bookmarked,
+                                contents,
+                            },
+                        );
+                    }
 
                     true
                 }
-                DiscreteContentResult::One(content) => {
-                    node.contents = content;
+                Err(contents) => {
+                    node.contents = contents;
                     self.nodes.insert(node.id, node);
                     false
                 }
@@ -737,6 +1372,46 @@ where
             .get_mut(id)
             .map(|node| callback(&mut node.contents))
     }
+    #[ensures(ret == self.nodes.contains_key(id))]
+    #[invariant(self.validate())]
+    fn with_node_mut(
+        &mut self,
+        id: &K,
+        callback: impl FnOnce(&mut DependentNode<K, T, S>),
+    ) -> bool {
+        let Some(node) = self.nodes.get_mut(id) else {
+            return false;
+        };
+        let (from, to, active, bookmarked) = (
+            node.from,
+            mem::take(&mut node.to),
+            node.active,
+            node.bookmarked,
+        );
+
+        callback(node);
+
+        let new_active = node.active;
+        node.id = *id;
+        node.from = from;
+        node.to = to;
+        node.active = active;
+
+        // The bookmark flag only needs to agree with the set of bookmarks.
+        if node.bookmarked != bookmarked {
+            if node.bookmarked {
+                self.bookmarked.insert(*id);
+            } else {
+                self.bookmarked.shift_remove(id);
+            }
+        }
+        // Writing the active flag directly could leave the active nodes without a single thread, so activity changes are made in place instead.
+        if new_active != active {
+            self.set_node_active_status_in_place(id, new_active);
+        }
+
+        true
+    }
 }
 
 impl<K, T, M, S> DeduplicatableWeave<K, DependentNode<K, T, S>, T> for DependentWeave<K, T, M, S>
@@ -756,6 +1431,12 @@ where
             })
         })
     }
+    fn find_duplicate_groups(&self) -> impl Iterator<Item = Vec<K>>
+    where
+        T: HashableContents,
+    {
+        duplicate_groups(self.nodes.values().map(|node| (node.id, &node.contents)))
+    }
 }
 
 #[cfg(feature = "rkyv")]
@@ -800,6 +1481,24 @@ where
     }
 }
 
+#[cfg(feature = "rkyv")]
+impl<K, T, M, S> ArchivedDependentWeave<K, T, M, S>
+where
+    K: Archive + Hash + Copy + Eq,
+    <K as Archive>::Archived: Hash + Eq,
+    T: Archive,
+    M: Archive,
+    S: BuildHasher + Default + Clone,
+{
+    /// Deserializes the archived weave into an owned [`DependentWeave`], without requiring access to the buffer the weave was archived into.
+    pub fn to_owned_weave(&self) -> Result<DependentWeave<K, T, M, S>, rancor::Error>
+    where
+        Self: Deserialize<DependentWeave<K, T, M, S>, Strategy<Pool, rancor::Error>>,
+    {
+        rkyv::deserialize(self)
+    }
+}
+
 #[cfg(feature = "rkyv")]
 impl<K, K2, T, T2, M, M2, S> ArchivedWeave<K::Archived, ArchivedDependentNode<K, T, S>, T::Archived>
     for ArchivedDependentWeave<K, T, M, S>
@@ -838,6 +1537,9 @@ where
     fn contains_bookmark(&self, id: &K::Archived) -> bool {
         self.bookmarked.contains(id)
     }
+    fn count_bookmarked(&self) -> usize {
+        self.bookmarked.len()
+    }
     fn get_node(&self, id: &K::Archived) -> Option<&ArchivedDependentNode<K, T, S>> {
         self.nodes.get(id)
     }
@@ -864,6 +1566,43 @@ where
 
         build_thread_archived(&self.nodes, *id, output);
     }
+    fn get_depth(&self, id: &K::Archived) -> Option<usize> {
+        node_depth(*id, self.nodes.len(), |id| {
+            self.nodes.get(id).map(|node| node.from.as_ref().copied())
+        })
+    }
+    fn subtree_size(&self, id: &K::Archived) -> Option<usize> {
+        subtree_size(*id, |id| {
+            self.nodes.get(id).map(|node| node.to.iter().copied())
+        })
+    }
+    fn search_contents(
+        &self,
+        predicate: impl Fn(&T::Archived) -> bool,
+    ) -> impl Iterator<Item = K::Archived> {
+        self.nodes
+            .values()
+            .filter(move |node| predicate(&node.contents))
+            .map(|node| node.id)
+    }
+    fn leaves(&self) -> impl Iterator<Item = K::Archived> {
+        let mut stack: Vec<K::Archived> = self.roots.iter().copied().collect();
+        stack.reverse();
+
+        iter::from_fn(move || {
+            loop {
+                let id = stack.pop()?;
+
+                if let Some(node) = self.nodes.get(&id) {
+                    if node.to.is_empty() {
+                        return Some(id);
+                    }
+
+                    stack.extend(node.to.iter().collect::<Vec<_>>().into_iter().rev());
+                }
+            }
+        })
+    }
 }
 
 #[cfg(feature = "rkyv")]
@@ -926,22 +1665,20 @@ where
     }
 }
 
-#[stacksafe]
 fn build_thread<K, T, S>(nodes: &HashMap<K, DependentNode<K, T, S>, S>, id: K, thread: &mut Vec<K>)
 where
     K: Hash + Copy + Eq,
     S: BuildHasher + Default + Clone,
 {
-    if let Some(node) = nodes.get(&id) {
-        thread.push(id);
-        if let Some(parent) = node.from {
-            build_thread(nodes, parent, thread);
-        }
+    let mut current = Some(id);
+
+    while let Some(node) = current.and_then(|id| nodes.get(&id)) {
+        thread.push(node.id);
+        current = node.from;
     }
 }
 
 #[cfg(feature = "rkyv")]
-#[stacksafe]
 fn build_thread_archived<K, K2, T, T2, S>(
     nodes: &ArchivedHashMap<K::Archived, ArchivedDependentNode<K, T, S>>,
     id: K::Archived,
@@ -952,11 +1689,14 @@ fn build_thread_archived<K, K2, T, T2, S>(
     T: Archive<Archived = T2>,
     S: BuildHasher + Default + Clone,
 {
-    if let Some(node) = nodes.get(&id) {
+    let mut current = Some(id);
+
+    while let Some((id, node)) = current.and_then(|id| Some((id, nodes.get(&id)?))) {
         thread.push(id);
-        if let ArchivedOption::Some(parent) = node.from {
-            build_thread_archived(nodes, parent, thread);
-        }
+        current = match node.from {
+            ArchivedOption::Some(parent) => Some(parent),
+            ArchivedOption::None => None,
+        };
     }
 }
 
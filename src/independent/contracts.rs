@@ -1,10 +1,9 @@
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     hash::{BuildHasher, Hash},
 };
 
 use indexmap::IndexSet;
-use stacksafe::stacksafe;
 
 #[allow(unused_imports)]
 use crate::Weave;
@@ -29,6 +28,7 @@ where
         let active_index: IndexSet<_, _> = self.active.iter().copied().collect();
 
         self.roots.is_subset::<S>(&nodes)
+            && self.scratchpads_clean()
             && self.validate_active()
             && self.active.is_subset(&nodes_std)
             && self.bookmarked.is_subset::<S>(&nodes)
@@ -57,49 +57,68 @@ where
                     }
             })
     }
-    fn validate_active(&self) -> bool {
-        let mut threads = Vec::new();
+    pub(super) fn validate_active(&self) -> bool {
+        // The active nodes form a single thread if and only if they have exactly one topological ordering, which starts at a root node
+        let mut remaining_parents: HashMap<K, usize, S> =
+            HashMap::with_capacity_and_hasher(self.active.len(), S::default());
 
-        for active_root in self.roots.iter().filter(|root| self.active.contains(root)) {
-            threads.push(Vec::new());
-            let index = threads.len() - 1;
-            if !self.build_path(active_root, &mut threads, index) {
-                return false;
+        for id in &self.active {
+            match self.nodes.get(id) {
+                Some(node) => {
+                    remaining_parents.insert(
+                        *id,
+                        node.from
+                            .iter()
+                            .filter(|parent| self.active.contains(*parent))
+                            .count(),
+                    );
+                }
+                None => return false,
             }
         }
 
-        let mut longest = (0, 0);
+        let mut ready: Vec<K> = remaining_parents
+            .iter()
+            .filter(|(_, remaining)| **remaining == 0)
+            .map(|(id, _)| *id)
+            .collect();
+        let mut visited = 0;
 
-        for (index, thread) in threads.iter().enumerate() {
-            if thread.len() > longest.0 {
-                longest = (thread.len(), index);
+        while let Some(id) = ready.pop() {
+            if !ready.is_empty() || (visited == 0 && !self.roots.contains(&id)) {
+                return false;
             }
-        }
-
-        if threads.is_empty() {
-            return self.active.is_empty();
-        }
 
-        let thread = threads.swap_remove(longest.1);
+            visited += 1;
 
-        thread.len() == self.active.len() && HashSet::from_iter(thread).is_subset(&self.active)
-    }
-    #[stacksafe]
-    fn build_path(&self, node: &K, threads: &mut Vec<Vec<K>>, index: usize) -> bool {
-        if let Some(node) = self.nodes.get(node) {
-            threads[index].push(node.id);
+            for child in self
+                .nodes
+                .get(&id)
+                .unwrap()
+                .to
+                .iter()
+                .filter(|child| self.active.contains(*child))
+            {
+                let remaining = remaining_parents.get_mut(child).unwrap();
+                *remaining -= 1;
 
-            for active_child in node.to.iter().filter(|root| self.active.contains(root)) {
-                threads.push(threads[index].clone());
-                if !self.build_path(active_child, threads, threads.len() - 1) {
-                    return false;
+                if *remaining == 0 {
+                    ready.push(*child);
                 }
             }
-
-            true
-        } else {
-            false
         }
+
+        visited == self.active.len()
+    }
+    /// Checks that the scratchpads reused internally by the weave's methods are empty.
+    ///
+    /// Scratchpads are expected to be cleared before every public method returns; a method which leaves stale data in them may cause later methods to behave incorrectly.
+    #[must_use]
+    pub fn scratchpads_clean(&self) -> bool {
+        self.scratchpad_list.is_empty()
+            && self.scratchpad_list_2.is_empty()
+            && self.scratchpad_set.is_empty()
+            && self.scratchpad_set_2.is_empty()
     }
     #[must_use]
     pub(super) fn under_max_size(&self) -> bool {
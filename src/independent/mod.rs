@@ -2,9 +2,9 @@
 
 use std::{
     cmp::Ordering,
-    collections::{HashMap, HashSet},
+    collections::{HashMap, HashSet, VecDeque},
     hash::{BuildHasher, Hash},
-    mem,
+    iter, mem,
 };
 
 use ::contracts::{ensures, invariant};
@@ -15,6 +15,8 @@ use stacksafe::stacksafe;
 use rkyv::{
     Archive, Deserialize, Serialize,
     collections::swiss_table::{ArchivedHashMap, ArchivedHashSet, ArchivedIndexSet},
+    de::Pool,
+    rancor::{self, Strategy},
     with::Skip,
 };
 
@@ -32,12 +34,15 @@ use crate::{
 
 use crate::{
     ActivePathWeave, DeduplicatableContents, DeduplicatableWeave, DiscreteContentResult,
-    DiscreteContents, DiscreteWeave, IndependentContents, IntegratedNode, MetadataWeave, Node,
-    SortableWeave, Weave, ancestor_subgraph,
+    DiscreteContents, DiscreteWeave, HashableContents, IndependentContents, IntegratedNode,
+    MetadataWeave, Node, NodeBuilder, SortableWeave, SplitError, TryReserveError, Weave,
+    WeaveError, ancestor_subgraph,
     contract::{lacks_duplicates, valid_ordered_nodes, valid_thread},
     dependent::DependentWeave,
-    descendant_subgraph, shortest_path_to_ancestor, shortest_path_to_descendant, topological_sort,
+    descendant_subgraph, duplicate_groups, node_depth, shortest_path_to_ancestor,
+    shortest_path_to_descendant, split_contents, subtree_size, topological_sort,
     topological_sort_rev,
+    wrappers::WeaveAction,
 };
 
 mod contracts;
@@ -80,6 +85,22 @@ where
     pub bookmarked: bool,
     /// The node's contents.
     pub contents: T,
+    /// The node's display style, if any.
+    pub style: Option<NodeStyle>,
+}
+
+/// Typed display information for an [`IndependentNode`].
+///
+/// This allows user interfaces to read a node's styling without parsing it out of the node's contents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "rkyv", derive(Archive, Deserialize, Serialize))]
+#[cfg_attr(feature = "wincode", derive(SchemaRead, SchemaWrite))]
+#[cfg_attr(feature = "serde", derive(SerdeSerialize, SerdeDeserialize))]
+pub struct NodeStyle {
+    /// The node's color in RGBA format.
+    pub color: [u8; 4],
+    /// If the node should be displayed as collapsed.
+    pub collapsed: bool,
 }
 
 impl<K, T, S> PartialEq for IndependentNode<K, T, S>
@@ -95,6 +116,7 @@ where
             && self.active.eq(&other.active)
             && self.bookmarked.eq(&other.bookmarked)
             && self.contents.eq(&other.contents)
+            && self.style.eq(&other.style)
     }
 }
 
@@ -119,6 +141,25 @@ where
     }
 }
 
+impl<K, T, S> From<NodeBuilder<K, T, S>> for IndependentNode<K, T, S>
+where
+    K: Hash + Copy + Eq,
+    T: IndependentContents,
+    S: BuildHasher + Default + Clone,
+{
+    fn from(value: NodeBuilder<K, T, S>) -> Self {
+        Self {
+            id: value.id,
+            from: value.parents,
+            to: IndexSet::default(),
+            active: value.active,
+            bookmarked: value.bookmarked,
+            contents: value.contents,
+            style: None,
+        }
+    }
+}
+
 impl<K, T, S> Node<K, T> for IndependentNode<K, T, S>
 where
     K: Hash + Copy + Eq,
@@ -233,7 +274,12 @@ where
     T: IndependentContents,
     S: BuildHasher + Default + Clone,
 {
+    /// Creates an empty weave with space for at least `capacity` nodes.
+    ///
+    /// The capacity is clamped to the maximum number of nodes the weave can contain.
     pub fn with_capacity(capacity: usize, metadata: M) -> Self {
+        let capacity = capacity.min(i32::MAX as usize);
+
         Self {
             nodes: HashMap::with_capacity_and_hasher(capacity, S::default()),
             roots: IndexSet::with_capacity_and_hasher(capacity, S::default()),
@@ -246,10 +292,177 @@ where
             metadata,
         }
     }
+    /// Constructs a weave directly from its nodes, root identifiers, active identifiers and bookmarked identifiers, without replaying [`Weave::add_node`].
+    ///
+    /// Returns the reason the weave was rejected if it would not be internally consistent (such as when a node's `active` or `bookmarked` fields disagree with the provided sets).
+    #[ensures(ret.as_ref().map_or(true, |weave| weave.validate()))]
+    pub fn rebuild_from_parts(
+        nodes: Vec<IndependentNode<K, T, S>>,
+        roots: IndexSet<K, S>,
+        active: HashSet<K, S>,
+        bookmarked: IndexSet<K, S>,
+        metadata: M,
+    ) -> Result<Self, WeaveError> {
+        let capacity = nodes.len();
+        let mut weave = Self::with_capacity(0, metadata);
+
+        weave.nodes.reserve(capacity);
+
+        for node in nodes {
+            if weave.nodes.insert(node.id, node).is_some() {
+                return Err(WeaveError::DuplicateNode);
+            }
+        }
+
+        weave.roots = roots;
+        weave.active = active;
+        weave.bookmarked = bookmarked;
+        weave.reserve(0);
+
+        if !weave.under_max_size() {
+            return Err(WeaveError::CapacityExceeded);
+        }
+
+        for node in weave.nodes.values() {
+            if !node.validate() {
+                return Err(WeaveError::InvalidNode);
+            }
+
+            if !node.from.iter().all(|parent| {
+                weave
+                    .nodes
+                    .get(parent)
+                    .is_some_and(|parent| parent.to.contains(&node.id))
+            }) || !node.to.iter().all(|child| {
+                weave
+                    .nodes
+                    .get(child)
+                    .is_some_and(|child| child.from.contains(&node.id))
+            }) {
+                return Err(WeaveError::DanglingEdge);
+            }
+
+            if node.from.is_empty() != weave.roots.contains(&node.id) {
+                return Err(WeaveError::InvalidRoots);
+            }
+            if node.active != weave.active.contains(&node.id) {
+                return Err(WeaveError::InvalidActive);
+            }
+            if node.bookmarked != weave.bookmarked.contains(&node.id) {
+                return Err(WeaveError::InvalidBookmarks);
+            }
+        }
+
+        if !weave.roots.iter().all(|id| weave.nodes.contains_key(id)) {
+            return Err(WeaveError::InvalidRoots);
+        }
+        if !weave.validate_active() {
+            return Err(WeaveError::InvalidActive);
+        }
+        if !weave
+            .bookmarked
+            .iter()
+            .all(|id| weave.nodes.contains_key(id))
+        {
+            return Err(WeaveError::InvalidBookmarks);
+        }
+
+        Ok(weave)
+    }
+    /// Constructs a weave from a list of nodes, without replaying [`Weave::add_node`].
+    ///
+    /// The children of each node are rebuilt from the parents of every other node. A node's existing list of children is kept if it contains exactly these children; otherwise, children are ordered by their positions in `nodes`. Root nodes and bookmarked nodes are also ordered by their positions in `nodes`.
+    ///
+    /// Returns `None` if multiple nodes share an identifier, a node's parent is not present, the nodes contain a cyclical connection, or the weave would otherwise not be internally consistent (such as when the active nodes do not form a single thread).
+    pub fn from_nodes(
+        nodes: impl IntoIterator<Item = IndependentNode<K, T, S>>,
+        metadata: M,
+    ) -> Option<Self> {
+        let nodes = nodes.into_iter();
+        let mut weave = Self::with_capacity(nodes.size_hint().0, metadata);
+        let mut order = Vec::with_capacity(nodes.size_hint().0);
+
+        for node in nodes {
+            let id = node.id;
+
+            if node.from.is_empty() {
+                weave.roots.insert(id);
+            }
+            if node.active {
+                weave.active.insert(id);
+            }
+            if node.bookmarked {
+                weave.bookmarked.insert(id);
+            }
+
+            if weave.nodes.insert(id, node).is_some() {
+                return None;
+            }
+
+            order.push(id);
+        }
+
+        let mut children: HashMap<K, IndexSet<K, S>, S> =
+            HashMap::with_capacity_and_hasher(weave.nodes.len(), S::default());
+
+        for id in &order {
+            for parent in &weave.nodes.get(id).unwrap().from {
+                if !weave.nodes.contains_key(parent) {
+                    return None;
+                }
+
+                children.entry(*parent).or_default().insert(*id);
+            }
+        }
+
+        for node in weave.nodes.values_mut() {
+            let derived = children.remove(&node.id).unwrap_or_default();
+
+            if node.to.len() != derived.len() || !node.to.iter().all(|id| derived.contains(id)) {
+                node.to = derived;
+            }
+        }
+
+        let mut remaining_parents: HashMap<K, usize, S> =
+            HashMap::with_capacity_and_hasher(weave.nodes.len(), S::default());
+        remaining_parents.extend(weave.nodes.values().map(|node| (node.id, node.from.len())));
+        let mut stack: Vec<K> = weave.roots.iter().copied().collect();
+        let mut visited = 0;
+
+        while let Some(id) = stack.pop() {
+            visited += 1;
+
+            for child in &weave.nodes.get(&id).unwrap().to {
+                let remaining = remaining_parents.get_mut(child).unwrap();
+                *remaining -= 1;
+
+                if *remaining == 0 {
+                    stack.push(*child);
+                }
+            }
+        }
+
+        if visited != weave.nodes.len() {
+            return None;
+        }
+
+        weave.reserve(0);
+
+        if weave.under_max_size() && weave.validate() {
+            Some(weave)
+        } else {
+            None
+        }
+    }
     pub fn capacity(&self) -> usize {
         self.nodes.capacity()
     }
+    /// Reserves space for at least `additional` more nodes.
+    ///
+    /// The reservation is clamped so that the weave's capacity does not exceed the maximum number of nodes it can contain.
     pub fn reserve(&mut self, additional: usize) {
+        let additional = additional.min((i32::MAX as usize).saturating_sub(self.nodes.len()));
+
         self.nodes.reserve(additional);
         self.roots
             .reserve(self.nodes.capacity().saturating_sub(self.roots.len()));
@@ -278,15 +491,1024 @@ where
                 .saturating_sub(self.scratchpad_set_2.len()),
         );
     }
-    pub fn shrink_to(&mut self, min_capacity: usize) {
-        self.nodes.shrink_to(min_capacity);
-        self.roots.shrink_to(min_capacity);
-        self.active.shrink_to(min_capacity);
-        self.bookmarked.shrink_to(min_capacity);
-        self.scratchpad_list.shrink_to(min_capacity);
-        self.scratchpad_list_2.shrink_to(min_capacity);
-        self.scratchpad_set.shrink_to(min_capacity);
-        self.scratchpad_set_2.shrink_to(min_capacity);
+    /// Tries to reserve space for at least `additional` more nodes, returning an error instead of aborting if an allocation fails.
+    ///
+    /// The reservation is clamped in the same way as [`IndependentWeave::reserve`]. If an error is returned, some collections may have already been grown.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let additional = additional.min((i32::MAX as usize).saturating_sub(self.nodes.len()));
+
+        self.nodes.try_reserve(additional)?;
+        self.roots
+            .try_reserve(self.nodes.capacity().saturating_sub(self.roots.len()))?;
+        self.active
+            .try_reserve(self.nodes.capacity().saturating_sub(self.active.len()))?;
+        self.bookmarked
+            .try_reserve(self.nodes.capacity().saturating_sub(self.bookmarked.len()))?;
+        self.scratchpad_list.try_reserve(
+            self.nodes
+                .capacity()
+                .saturating_sub(self.scratchpad_list.len()),
+        )?;
+        self.scratchpad_list_2.try_reserve(
+            self.nodes
+                .capacity()
+                .saturating_sub(self.scratchpad_list_2.len()),
+        )?;
+        self.scratchpad_set.try_reserve(
+            self.nodes
+                .capacity()
+                .saturating_sub(self.scratchpad_set.len()),
+        )?;
+        self.scratchpad_set_2.try_reserve(
+            self.nodes
+                .capacity()
+                .saturating_sub(self.scratchpad_set_2.len()),
+        )?;
+
+        Ok(())
+    }
+    pub fn shrink_to(&mut self, min_capacity: usize) {
+        self.nodes.shrink_to(min_capacity);
+        self.roots.shrink_to(min_capacity);
+        self.active.shrink_to(min_capacity);
+        self.bookmarked.shrink_to(min_capacity);
+        self.scratchpad_list.shrink_to(min_capacity);
+        self.scratchpad_list_2.shrink_to(min_capacity);
+        self.scratchpad_set.shrink_to(min_capacity);
+        self.scratchpad_set_2.shrink_to(min_capacity);
+    }
+    /// Removes every node for which `predicate` returns `false`.
+    ///
+    /// Nodes are removed using the same semantics as [`Weave::remove_node`], starting from the most deeply nested nodes; removing a node also removes any children which would otherwise be left without a parent, regardless of whether `predicate` returned `true` for them.
+    #[ensures(old(self.nodes.len()) >= self.nodes.len())]
+    #[invariant(self.validate())]
+    pub fn retain(&mut self, mut predicate: impl FnMut(&IndependentNode<K, T, S>) -> bool) {
+        let mut identifiers = Vec::with_capacity(self.nodes.len());
+        self.get_ordered_node_identifiers(&mut identifiers);
+        identifiers.retain(|id| !predicate(self.nodes.get(id).unwrap()));
+
+        if identifiers.is_empty() {
+            return;
+        }
+
+        for id in identifiers.iter().rev() {
+            self.remove_node_unverified(id);
+        }
+
+        self.fix_orphaned_activations();
+    }
+    /// Removes every node which is not part of the active thread (see [`Weave::get_active_thread`]).
+    ///
+    /// Bookmarks on removed nodes are discarded. If the weave does not have an active thread, every node is removed.
+    #[ensures(old(self.active.clone()) == self.active)]
+    #[ensures(self.nodes.len() == self.active.len())]
+    #[ensures(self.scratchpads_clean())]
+    #[invariant(self.validate())]
+    pub fn prune_to_active(&mut self) {
+        self.retain(|node| node.active);
+    }
+    /// Removes all nodes from the weave while keeping its allocated capacity, leaving the weave's metadata untouched.
+    #[ensures(self.nodes.is_empty())]
+    #[ensures(old(self.capacity()) <= self.capacity())]
+    #[invariant(self.validate())]
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+        self.roots.clear();
+        self.active.clear();
+        self.bookmarked.clear();
+        self.clear_scratchpads();
+    }
+    /// Deactivates every active node, leaving the weave without an active thread.
+    #[ensures(self.active.is_empty())]
+    #[ensures(old(self.nodes.len()) == self.nodes.len())]
+    #[invariant(self.validate())]
+    pub fn clear_active(&mut self) {
+        for id in self.active.drain() {
+            if let Some(node) = self.nodes.get_mut(&id) {
+                node.active = false;
+            }
+        }
+    }
+    /// Activates the node with the specified identifier and then repeatedly activates the first child of the most recently activated node until a node without any children is reached, returning `false` if the node could not be found.
+    ///
+    /// Each node is activated as if by [`Weave::set_node_active_status_in_place`], so the resulting active thread passes through the specified node and ends at a node without any children.
+    #[ensures(ret == self.nodes.contains_key(id))]
+    #[ensures(!ret || self.active.contains(id))]
+    #[ensures(!ret || self.active.iter().filter_map(|active| self.nodes.get(active)).any(|node| node.to.is_empty()))]
+    #[ensures(self.scratchpads_clean())]
+    #[invariant(self.validate())]
+    pub fn activate_to_leaf(&mut self, id: &K) -> bool {
+        if !self.update_node_activity_in_place(id, true) {
+            return false;
+        }
+
+        let mut current = *id;
+
+        while let Some(child) = self
+            .nodes
+            .get(&current)
+            .and_then(|node| node.to.first())
+            .copied()
+        {
+            self.update_node_activity_in_place(&child, true);
+            current = child;
+        }
+
+        true
+    }
+    /// Inserts a batch of nodes into the weave, returning a list indicating which nodes were successfully inserted in the order they were specified in.
+    ///
+    /// Unlike calling [`Weave::add_node`] repeatedly, nodes may be specified before the nodes they are connected to; insertion of a node is deferred until every node it is connected to is present in the weave. Changes to the active status of nodes are applied once all nodes have been inserted.
+    #[ensures(old(self.nodes.len()) + ret.iter().filter(|inserted| **inserted).count() == self.nodes.len())]
+    #[invariant(self.validate())]
+    pub fn add_nodes(
+        &mut self,
+        nodes: impl IntoIterator<Item = IndependentNode<K, T, S>>,
+    ) -> Vec<bool> {
+        let mut nodes: Vec<_> = nodes.into_iter().map(Some).collect();
+        let mut results = vec![false; nodes.len()];
+        let mut waiting: HashMap<K, Vec<usize>, S> = HashMap::default();
+        let mut ready: Vec<usize> = (0..nodes.len()).rev().collect();
+        let mut activations = Vec::new();
+
+        while let Some(index) = ready.pop() {
+            let node = nodes[index].as_ref().unwrap();
+
+            if let Some(missing) = node
+                .from
+                .iter()
+                .chain(node.to.iter())
+                .find(|id| !self.nodes.contains_key(id))
+            {
+                waiting.entry(*missing).or_default().push(index);
+                continue;
+            }
+
+            let mut node = nodes[index].take().unwrap();
+            let id = node.id;
+            let active = mem::replace(&mut node.active, false);
+
+            if self.add_node(node) {
+                results[index] = true;
+
+                if active {
+                    activations.push(id);
+                }
+
+                if let Some(waiters) = waiting.remove(&id) {
+                    ready.extend(waiters.into_iter().rev());
+                }
+            }
+        }
+
+        for id in activations {
+            self.update_node_activity_in_place(&id, true);
+        }
+
+        results
+    }
+    /// Returns the display style of a node with the specified identifier, if the node exists and has a style.
+    pub fn get_node_style(&self, id: &K) -> Option<NodeStyle> {
+        self.nodes.get(id).and_then(|node| node.style)
+    }
+    /// Sets the display style of a node with the specified identifier.
+    ///
+    /// Returns `false` if the node could not be found.
+    pub fn set_node_style(&mut self, id: &K, style: Option<NodeStyle>) -> bool {
+        match self.nodes.get_mut(id) {
+            Some(node) => {
+                node.style = style;
+
+                true
+            }
+            None => false,
+        }
+    }
+    /// Bookmarks every node for which `predicate` returns `true`, returning the number of nodes which were not previously bookmarked.
+    ///
+    /// Newly bookmarked nodes are appended to the list of bookmarks in an unspecified order.
+    #[ensures(self.nodes.values().all(|node| !predicate(node) || node.bookmarked))]
+    #[ensures(old(self.bookmarked.len()) + ret == self.bookmarked.len())]
+    #[invariant(self.validate())]
+    pub fn bookmark_matching(
+        &mut self,
+        predicate: impl Fn(&IndependentNode<K, T, S>) -> bool,
+    ) -> usize {
+        let mut count = 0;
+
+        for node in self.nodes.values_mut() {
+            if !node.bookmarked && predicate(node) {
+                node.bookmarked = true;
+                self.bookmarked.insert(node.id);
+                count += 1;
+            }
+        }
+
+        count
+    }
+    /// Removes the bookmark from every node for which `predicate` returns `true`, returning the number of nodes which were previously bookmarked.
+    #[ensures(self.nodes.values().all(|node| !predicate(node) || !node.bookmarked))]
+    #[ensures(old(self.bookmarked.len()) - ret == self.bookmarked.len())]
+    #[invariant(self.validate())]
+    pub fn unbookmark_matching(
+        &mut self,
+        predicate: impl Fn(&IndependentNode<K, T, S>) -> bool,
+    ) -> usize {
+        let mut count = 0;
+
+        for node in self.nodes.values_mut() {
+            if node.bookmarked && predicate(node) {
+                node.bookmarked = false;
+                self.bookmarked.shift_remove(&node.id);
+                count += 1;
+            }
+        }
+
+        count
+    }
+    /// Makes `keep` the only active child of `parent`, deactivating every other active child of `parent` (along with any nodes which were only active through them) and activating `keep` if it was not already active.
+    ///
+    /// Returns `false` if either node does not exist or if `keep` is not a child of `parent`.
+    #[ensures(!ret || self.active.contains(keep))]
+    #[ensures(ret || old(self.active.clone()) == self.active)]
+    #[invariant(self.validate())]
+    pub fn make_sole_active_child(&mut self, parent: &K, keep: &K) -> bool {
+        let Some(parent_node) = self.nodes.get(parent) else {
+            return false;
+        };
+
+        if !parent_node.to.contains(keep) {
+            return false;
+        }
+
+        let active_children: Vec<K> = parent_node
+            .to
+            .iter()
+            .copied()
+            .filter(|child| child != keep && self.active.contains(child))
+            .collect();
+
+        for child in active_children {
+            self.update_node_activity_in_place(&child, false);
+        }
+
+        self.update_node_activity_in_place(keep, true)
+    }
+    /// Returns the identifier of the deepest node which is both an ancestor of (or equal to) `a` and an ancestor of (or equal to) `b`.
+    ///
+    /// Returns `None` if either node does not exist or if the nodes do not share any ancestors. If multiple such nodes exist at an equal depth (as determined by [`Weave::get_depth`]), the node with the lowest identifier is returned.
+    #[ensures(ret.is_none() || self.nodes.contains_key(&ret.unwrap()))]
+    pub fn lowest_common_ancestor(&self, a: &K, b: &K) -> Option<K>
+    where
+        K: Ord,
+    {
+        if !self.nodes.contains_key(a) || !self.nodes.contains_key(b) {
+            return None;
+        }
+
+        let a_ancestors: HashSet<K, S> = iter::once(*a).chain(self.ancestors(a)).collect();
+        let common: Vec<K> = iter::once(*b)
+            .chain(self.ancestors(b))
+            .filter(|id| a_ancestors.contains(id))
+            .collect();
+
+        let mut dominated: HashSet<K, S> = HashSet::default();
+        let mut stack: Vec<K> = common
+            .iter()
+            .flat_map(|id| self.nodes.get(id).unwrap().from.iter().copied())
+            .collect();
+
+        while let Some(id) = stack.pop() {
+            if dominated.insert(id)
+                && let Some(node) = self.nodes.get(&id)
+            {
+                stack.extend(node.from.iter().copied());
+            }
+        }
+
+        common
+            .into_iter()
+            .filter(|id| !dominated.contains(id))
+            .map(|id| (self.get_depth(&id).unwrap_or_default(), id))
+            .max_by(|(a_depth, a_id), (b_depth, b_id)| {
+                a_depth.cmp(b_depth).then_with(|| b_id.cmp(a_id))
+            })
+            .map(|(_, id)| id)
+    }
+    /// Returns the shortest list of directly connected nodes starting at `from` and ending at `to`, or `None` if either node does not exist or if the nodes are not connected.
+    ///
+    /// Connections are traversed in both directions, so the returned path may pass through both parents and children.
+    #[ensures(ret.as_ref().is_none_or(|path| path.first() == Some(from) && path.last() == Some(to)))]
+    #[ensures(ret.as_ref().is_none_or(lacks_duplicates))]
+    #[ensures(self.scratchpads_clean())]
+    pub fn shortest_path(&mut self, from: &K, to: &K) -> Option<Vec<K>> {
+        if !self.nodes.contains_key(from) || !self.nodes.contains_key(to) {
+            return None;
+        }
+
+        let mut parents = vec![0];
+        let mut index = 0;
+        let mut found = None;
+
+        self.scratchpad_list.push(*from);
+        self.scratchpad_set.insert(*from);
+
+        while let Some(id) = self.scratchpad_list.get(index).copied() {
+            if id == *to {
+                found = Some(index);
+                break;
+            }
+
+            let node = self.nodes.get(&id).unwrap();
+
+            for neighbor in node.from.iter().chain(node.to.iter()) {
+                if self.scratchpad_set.insert(*neighbor) {
+                    self.scratchpad_list.push(*neighbor);
+                    parents.push(index);
+                }
+            }
+
+            index += 1;
+        }
+
+        let path = found.map(|mut index| {
+            let mut path = vec![self.scratchpad_list[index]];
+
+            while index != 0 {
+                index = parents[index];
+                path.push(self.scratchpad_list[index]);
+            }
+
+            path.reverse();
+            path
+        });
+
+        self.scratchpad_list.clear();
+        self.scratchpad_set.clear();
+
+        path
+    }
+    /// Calls `f` on each node within the active thread, in the same order as [`Weave::get_active_thread`].
+    ///
+    /// Active threads of up to 512 nodes are walked recursively on the call stack without allocating on the heap. Longer active threads are walked using heap-allocated working memory instead.
+    pub fn for_each_active<'a>(&'a self, mut f: impl FnMut(&'a IndependentNode<K, T, S>)) {
+        let Some(root) = self
+            .roots
+            .iter()
+            .find(|root| self.active.contains(*root))
+            .and_then(|root| self.nodes.get(root))
+        else {
+            return;
+        };
+
+        if for_each_active_from(&self.nodes, &self.active, root, None, 1, &mut f) {
+            return;
+        }
+
+        let mut thread = Vec::with_capacity(self.active.len());
+        let mut visited: HashSet<K, S> =
+            HashSet::with_capacity_and_hasher(self.active.len(), S::default());
+        let mut current = Some(root);
+
+        while let Some(node) = current {
+            thread.push(node);
+            visited.insert(node.id);
+
+            current = next_active_child(&self.nodes, &self.active, node, |parent| {
+                visited.contains(parent)
+            });
+        }
+
+        for node in thread.into_iter().rev() {
+            f(node);
+        }
+    }
+    /// Returns the identifier of the sibling immediately after the specified node, in the order of its first parent's children (or the order of root nodes if the node is a root node).
+    ///
+    /// Returns `None` if the node does not exist or if it is the last of its siblings.
+    pub fn next_sibling(&self, id: &K) -> Option<K> {
+        let siblings = self.sibling_set(id)?;
+
+        siblings.get_index(siblings.get_index_of(id)? + 1).copied()
+    }
+    /// Returns the identifier of the sibling immediately before the specified node, in the order of its first parent's children (or the order of root nodes if the node is a root node).
+    ///
+    /// Returns `None` if the node does not exist or if it is the first of its siblings.
+    pub fn prev_sibling(&self, id: &K) -> Option<K> {
+        let siblings = self.sibling_set(id)?;
+
+        siblings
+            .get_index(siblings.get_index_of(id)?.checked_sub(1)?)
+            .copied()
+    }
+    fn sibling_set(&self, id: &K) -> Option<&IndexSet<K, S>> {
+        let node = self.nodes.get(id)?;
+
+        match node.from.first() {
+            Some(parent) => self.nodes.get(parent).map(|parent| &parent.to),
+            None => Some(&self.roots),
+        }
+    }
+    /// Moves every child of the node `from` to the node `to`, leaving `from` without any children.
+    ///
+    /// Returns `false` if either node does not exist or if `to` is `from` or one of its descendants.
+    ///
+    /// This function may change the active status of other nodes if it is necessary to preserve internal consistency.
+    #[ensures(!ret || self.nodes.get(from).unwrap().to.is_empty())]
+    #[ensures(!ret || old(self.nodes.get(from).map(|node| node.to.clone()).unwrap_or_default()).iter().all(|child| self.nodes.get(to).unwrap().to.contains(child)))]
+    #[ensures(ret || old(self.active.clone()) == self.active)]
+    #[ensures(old(self.nodes.len()) == self.nodes.len())]
+    #[ensures(old(self.bookmarked.clone()) == self.bookmarked)]
+    #[invariant(self.validate())]
+    pub fn move_all_children(&mut self, from: &K, to: &K) -> bool {
+        if !self.nodes.contains_key(from) || !self.nodes.contains_key(to) {
+            return false;
+        }
+
+        self.scratchpad_set.clear();
+        descendant_subgraph(&self.nodes, from, &mut self.scratchpad_set);
+
+        if self.scratchpad_set.contains(to) {
+            self.scratchpad_set.clear();
+            return false;
+        }
+
+        self.scratchpad_set.clear();
+
+        let from_node = self.nodes.get_mut(from).unwrap();
+        let children = mem::take(&mut from_node.to);
+        let was_active = from_node.active;
+
+        for child in &children {
+            if let Some(child) = self.nodes.get_mut(child) {
+                child.from.shift_remove(from);
+                child.from.insert(*to);
+            }
+        }
+
+        self.nodes.get_mut(to).unwrap().to.extend(children);
+
+        if was_active {
+            self.fix_orphaned_activations();
+        }
+
+        true
+    }
+    /// Returns true if any of the specified identifiers belong to a node in this weave.
+    pub fn keys_overlap(&self, mut other_keys: impl Iterator<Item = K>) -> bool {
+        other_keys.any(|id| self.nodes.contains_key(&id))
+    }
+    /// Consumes the weave, returning an equivalent weave whose node identifiers have been rewritten using the specified function.
+    ///
+    /// The structure of the weave (including the order of children, roots and bookmarks) and the status of every node are preserved. The caller is responsible for ensuring `f` maps distinct identifiers to distinct identifiers; a non-injective mapping produces an internally inconsistent weave.
+    pub fn remap_keys<K2>(self, f: impl Fn(K) -> K2) -> IndependentWeave<K2, T, M, S>
+    where
+        K2: Hash + Copy + Eq,
+    {
+        let mut weave = IndependentWeave::with_capacity(0, self.metadata);
+
+        weave.nodes.reserve(self.nodes.len());
+
+        for (id, node) in self.nodes {
+            let id = f(id);
+            weave.nodes.insert(
+                id,
+                IndependentNode {
+                    id,
+                    from: node.from.into_iter().map(&f).collect(),
+                    to: node.to.into_iter().map(&f).collect(),
+                    active: node.active,
+                    bookmarked: node.bookmarked,
+                    contents: node.contents,
+                    style: node.style,
+                },
+            );
+        }
+
+        weave.roots = self.roots.into_iter().map(&f).collect();
+        weave.active = self.active.into_iter().map(&f).collect();
+        weave.bookmarked = self.bookmarked.into_iter().map(&f).collect();
+        weave.reserve(0);
+
+        weave
+    }
+    /// Inserts every node of another weave into this weave, connecting each of the other weave's root nodes as children of the node with the specified identifier.
+    ///
+    /// Grafted nodes are inserted inactive and keep their bookmark statuses; the other weave's metadata is discarded. Returns false without modifying the weave if the parent node does not exist, if any identifier is present in both weaves, or if the combined weave would exceed the maximum number of nodes.
+    #[ensures(!ret || old(self.nodes.len()) + old(other.nodes.len()) == self.nodes.len())]
+    #[ensures(ret || old(self.nodes.len()) == self.nodes.len())]
+    #[ensures(old(self.active.clone()) == self.active)]
+    #[invariant(self.validate())]
+    pub fn graft(&mut self, other: IndependentWeave<K, T, M, S>, under: &K) -> bool {
+        if !self.nodes.contains_key(under)
+            || (self.nodes.len() as u64 + other.nodes.len() as u64) >= (i32::MAX as u64)
+            || self.keys_overlap(other.nodes.keys().copied())
+        {
+            return false;
+        }
+
+        self.reserve(other.nodes.len());
+
+        for (id, mut node) in other.nodes {
+            node.active = false;
+            if node.from.is_empty() {
+                node.from.insert(*under);
+            }
+            self.nodes.insert(id, node);
+        }
+
+        self.nodes.get_mut(under).unwrap().to.extend(other.roots);
+        self.bookmarked.extend(other.bookmarked);
+
+        true
+    }
+    /// Merges sibling nodes whose contents are duplicates of each other (see [`DeduplicatableContents::is_duplicate_of`]), returning the number of nodes which were removed.
+    ///
+    /// Siblings are compared starting from the root nodes and continuing recursively through the children of every remaining node. When two siblings are duplicates, the later sibling's parents and children are moved onto the earlier sibling and the later sibling is removed. The surviving node is bookmarked if either node was bookmarked. If the active thread passed through a merged node, the active thread is rebuilt from its original tip (or the node that tip was merged into).
+    ///
+    /// Only siblings are deduplicated; duplicate nodes which do not share a parent (or are not both root nodes) are left unchanged. Siblings are not merged if doing so would create a cyclical connection.
+    #[ensures(old(self.nodes.len()) == self.nodes.len() + ret)]
+    #[ensures(self.scratchpads_clean())]
+    #[invariant(self.validate())]
+    pub fn deduplicate_all(&mut self) -> usize
+    where
+        T: DeduplicatableContents,
+    {
+        let mut removed = 0;
+        let mut active_thread = Vec::new();
+        self.get_active_thread(&mut active_thread);
+        let mut active_tip = active_thread.first().copied();
+        let mut touched_active = false;
+        let mut queue: Vec<Option<K>> = vec![None];
+        let mut visited: HashSet<K, S> = HashSet::default();
+
+        while let Some(parent) = queue.pop() {
+            let siblings: Vec<K> = match parent {
+                Some(parent) => match self.nodes.get(&parent) {
+                    Some(node) => node.to.iter().copied().collect(),
+                    None => continue,
+                },
+                None => self.roots.iter().copied().collect(),
+            };
+
+            let survivors = self.merge_duplicate_siblings(
+                siblings,
+                &mut removed,
+                &mut active_tip,
+                &mut touched_active,
+                &mut visited,
+            );
+
+            queue.extend(
+                survivors
+                    .into_iter()
+                    .rev()
+                    .filter(|survivor| visited.insert(*survivor))
+                    .map(Some),
+            );
+        }
+
+        if touched_active && let Some(active_tip) = active_tip {
+            self.rebuild_active_thread(&active_tip);
+        }
+
+        removed
+    }
+    /// Merges children of the node with the specified identifier whose contents are duplicates of each other (see [`DeduplicatableContents::is_duplicate_of`]), returning the number of nodes which were removed.
+    ///
+    /// Children are merged in the same way as [`IndependentWeave::deduplicate_all`], but descendants of the node's children are not deduplicated.
+    #[ensures(old(self.nodes.len()) == self.nodes.len() + ret)]
+    #[ensures(self.scratchpads_clean())]
+    #[invariant(self.validate())]
+    pub fn merge_duplicate_children(&mut self, parent: &K) -> usize
+    where
+        T: DeduplicatableContents,
+    {
+        let Some(children) = self
+            .nodes
+            .get(parent)
+            .map(|node| node.to.iter().copied().collect())
+        else {
+            return 0;
+        };
+
+        let mut removed = 0;
+        let mut active_thread = Vec::new();
+        self.get_active_thread(&mut active_thread);
+        let mut active_tip = active_thread.first().copied();
+        let mut touched_active = false;
+
+        self.merge_duplicate_siblings(
+            children,
+            &mut removed,
+            &mut active_tip,
+            &mut touched_active,
+            &mut HashSet::default(),
+        );
+
+        if touched_active && let Some(active_tip) = active_tip {
+            self.rebuild_active_thread(&active_tip);
+        }
+
+        removed
+    }
+    fn merge_duplicate_siblings(
+        &mut self,
+        siblings: Vec<K>,
+        removed: &mut usize,
+        active_tip: &mut Option<K>,
+        touched_active: &mut bool,
+        visited: &mut HashSet<K, S>,
+    ) -> Vec<K>
+    where
+        T: DeduplicatableContents,
+    {
+        let mut survivors: Vec<K> = Vec::with_capacity(siblings.len());
+
+        for sibling in siblings {
+            let Some(node) = self.nodes.get(&sibling) else {
+                continue;
+            };
+
+            let survivor = survivors.iter().copied().find(|survivor| {
+                self.nodes
+                    .get(survivor)
+                    .is_some_and(|survivor| survivor.contents.is_duplicate_of(&node.contents))
+            });
+
+            let is_active = survivor.is_some_and(|survivor| {
+                self.active.contains(&survivor) || self.active.contains(&sibling)
+            });
+
+            match survivor {
+                Some(survivor) if self.merge_duplicate(&sibling, &survivor) => {
+                    *removed += 1;
+                    *touched_active |= is_active;
+                    self.active.remove(&sibling);
+                    visited.remove(&survivor);
+
+                    if *active_tip == Some(sibling) {
+                        *active_tip = Some(survivor);
+                    }
+                }
+                _ => survivors.push(sibling),
+            }
+        }
+
+        survivors
+    }
+    fn rebuild_active_thread(&mut self, active_tip: &K) {
+        for id in self.active.drain() {
+            if let Some(node) = self.nodes.get_mut(&id) {
+                node.active = false;
+            }
+        }
+
+        self.update_node_activity_in_place(active_tip, true);
+    }
+    fn merge_duplicate(&mut self, duplicate: &K, survivor: &K) -> bool {
+        self.scratchpad_set.clear();
+        descendant_subgraph(&self.nodes, duplicate, &mut self.scratchpad_set);
+
+        if self.scratchpad_set.contains(survivor) {
+            self.scratchpad_set.clear();
+            return false;
+        }
+
+        self.scratchpad_set.clear();
+        descendant_subgraph(&self.nodes, survivor, &mut self.scratchpad_set);
+
+        let mut node = self.nodes.remove(duplicate).unwrap();
+
+        for parent in node.from.drain(..) {
+            let can_link = !self.scratchpad_set.contains(&parent);
+            let survivor_node = self.nodes.get_mut(survivor).unwrap();
+            let is_linked = survivor_node.from.contains(&parent);
+
+            if can_link && !is_linked {
+                survivor_node.from.insert(parent);
+            }
+
+            let parent = self.nodes.get_mut(&parent).unwrap();
+
+            if let Some(index) = parent.to.get_index_of(duplicate)
+                && (!can_link || is_linked || parent.to.replace_index(index, *survivor).is_err())
+            {
+                parent.to.shift_remove_index(index);
+            }
+        }
+
+        self.scratchpad_set.clear();
+
+        for child in node.to.drain(..) {
+            let child_node = self.nodes.get_mut(&child).unwrap();
+
+            if let Some(index) = child_node.from.get_index_of(duplicate)
+                && child_node.from.replace_index(index, *survivor).is_err()
+            {
+                child_node.from.shift_remove_index(index);
+            }
+
+            self.nodes.get_mut(survivor).unwrap().to.insert(child);
+        }
+
+        self.roots.shift_remove(duplicate);
+
+        if self.bookmarked.shift_remove(duplicate) {
+            self.nodes.get_mut(survivor).unwrap().bookmarked = true;
+            self.bookmarked.insert(*survivor);
+        }
+
+        true
+    }
+    /// Returns a list of actions which transform this Weave into `new` when applied in order (see [`ActionableWeave::apply`](crate::wrappers::ActionableWeave::apply)).
+    ///
+    /// Nodes are matched by identifier. Nodes only present in `new` are added, nodes only present in this Weave are removed, nodes whose parents differ are moved, and nodes whose contents differ (compared using [`PartialEq`]) have their contents replaced. Child, root, and bookmark orderings, bookmarked statuses, the active thread, and metadata are also updated to match `new`.
+    ///
+    /// Node styles are excluded from this guarantee: they are only carried over for added nodes, as there is no [`WeaveAction`] which changes the style of an existing node. Applying the returned actions therefore produces a Weave equal to `new` except for the styles of nodes present in both Weaves, which keep their original styles.
+    #[allow(clippy::type_complexity)]
+    pub fn diff(&self, new: &Self) -> Vec<WeaveAction<K, IndependentNode<K, T, S>, T, M>>
+    where
+        T: Clone + PartialEq,
+        M: Clone + PartialEq,
+    {
+        let mut actions = Vec::new();
+        let mut identifier_set = HashSet::with_capacity_and_hasher(new.nodes.len(), S::default());
+        let mut new_order = Vec::with_capacity(new.nodes.len());
+        let mut old_order = Vec::with_capacity(self.nodes.len());
+
+        for root in &new.roots {
+            topological_sort::<K, IndependentNode<K, T, S>, T, S>(
+                &new.nodes,
+                root,
+                &mut new_order,
+                &mut identifier_set,
+            );
+        }
+
+        identifier_set.clear();
+
+        for root in &self.roots {
+            topological_sort::<K, IndependentNode<K, T, S>, T, S>(
+                &self.nodes,
+                root,
+                &mut old_order,
+                &mut identifier_set,
+            );
+        }
+
+        if self.metadata != new.metadata {
+            actions.push(WeaveAction::SetMetadata(new.metadata.clone()));
+        }
+
+        let moved: HashSet<K, S> = new_order
+            .iter()
+            .filter(|id| {
+                self.nodes
+                    .get(id)
+                    .is_some_and(|node| !node.from.iter().eq(&new.nodes.get(id).unwrap().from))
+            })
+            .copied()
+            .collect();
+        let removed: Vec<K> = old_order
+            .iter()
+            .rev()
+            .filter(|id| !new.nodes.contains_key(id))
+            .copied()
+            .collect();
+
+        let reset_active = self.active != new.active
+            || (!self.active.is_empty() && (!moved.is_empty() || !removed.is_empty()));
+
+        if reset_active {
+            actions.extend(
+                self.roots
+                    .iter()
+                    .filter(|root| self.active.contains(root))
+                    .map(|root| WeaveAction::SetNodeActiveStatusInPlace {
+                        id: *root,
+                        value: false,
+                    }),
+            );
+        }
+
+        let mut touched: HashSet<K, S> = HashSet::default();
+        let mut touched_roots = false;
+
+        for id in &new_order {
+            let node = new.nodes.get(id).unwrap();
+
+            if !self.nodes.contains_key(id) {
+                let mut node = node.clone();
+                node.to.clear();
+                node.active = false;
+
+                touched.extend(node.from.iter().copied());
+                touched_roots |= node.from.is_empty();
+                actions.push(WeaveAction::AddNode(node));
+            } else if moved.contains(id) {
+                let old_parents = &self.nodes.get(id).unwrap().from;
+
+                touched.extend(old_parents.iter().chain(&node.from).copied());
+                touched_roots = true;
+                actions.push(WeaveAction::MoveNode {
+                    id: *id,
+                    new_parents: node.from.iter().copied().collect(),
+                });
+            }
+        }
+
+        actions.extend(removed.iter().map(|id| WeaveAction::RemoveNode(*id)));
+
+        for id in &new_order {
+            let node = new.nodes.get(id).unwrap();
+
+            if let Some(old_node) = self.nodes.get(id) {
+                if old_node.contents != node.contents {
+                    actions.push(WeaveAction::SetNodeContent {
+                        id: *id,
+                        contents: node.contents.clone(),
+                    });
+                }
+                if old_node.bookmarked != node.bookmarked {
+                    actions.push(WeaveAction::SetNodeBookmarkedStatus {
+                        id: *id,
+                        value: node.bookmarked,
+                    });
+                }
+            }
+
+            if node.to.len() > 1
+                && (touched.contains(id)
+                    || self
+                        .nodes
+                        .get(id)
+                        .is_none_or(|old_node| !old_node.to.iter().eq(&node.to)))
+            {
+                actions.push(WeaveAction::SetNodeChildOrdering {
+                    parent: Some(*id),
+                    children: node.to.iter().copied().collect(),
+                });
+            }
+        }
+
+        if new.roots.len() > 1 && (touched_roots || !self.roots.iter().eq(&new.roots)) {
+            actions.push(WeaveAction::SetNodeChildOrdering {
+                parent: None,
+                children: new.roots.iter().copied().collect(),
+            });
+        }
+
+        if new.bookmarked.len() > 1 && !self.bookmarked.iter().eq(&new.bookmarked) {
+            actions.push(WeaveAction::SetBookmarkOrdering(
+                new.bookmarked.iter().copied().collect(),
+            ));
+        }
+
+        if reset_active {
+            actions.extend(
+                new.active_thread_vec()
+                    .into_iter()
+                    .rev()
+                    .map(|id| WeaveAction::SetNodeActiveStatusInPlace { id, value: true }),
+            );
+        }
+
+        actions
+    }
+    /// Applies a list of actions (such as those returned by [`IndependentWeave::diff`]) in order, returning `false` if an action could not be applied.
+    ///
+    /// Adding a node which is already present and removing a node which is not present are treated as no-ops, allowing the same list of actions to be applied more than once. Any other action which fails (such as one referencing a missing node) stops processing; actions before the failed action remain applied, and the Weave is left in a valid state.
+    ///
+    /// [`WeaveAction::SplitNode`] and [`WeaveAction::MergeNodeWithParent`] always fail, as they require contents implementing [`DiscreteContents`]; use [`IndependentWeave::apply_discrete_actions`] to apply them.
+    #[ensures(self.scratchpads_clean())]
+    #[invariant(self.validate())]
+    #[allow(clippy::type_complexity)]
+    pub fn apply_actions(
+        &mut self,
+        actions: &[WeaveAction<K, IndependentNode<K, T, S>, T, M>],
+    ) -> bool
+    where
+        T: Clone,
+        M: Clone,
+    {
+        actions.iter().all(|action| self.apply_action(action))
+    }
+    /// Applies a list of actions in order, returning `false` if an action could not be applied.
+    ///
+    /// This behaves identically to [`IndependentWeave::apply_actions`], except that [`WeaveAction::SplitNode`] and [`WeaveAction::MergeNodeWithParent`] are also supported.
+    #[ensures(self.scratchpads_clean())]
+    #[invariant(self.validate())]
+    #[allow(clippy::type_complexity)]
+    pub fn apply_discrete_actions(
+        &mut self,
+        actions: &[WeaveAction<K, IndependentNode<K, T, S>, T, M>],
+    ) -> bool
+    where
+        T: Clone + DiscreteContents,
+        M: Clone,
+    {
+        actions.iter().all(|action| match action {
+            WeaveAction::SplitNode { id, at, new_id } => self.split_node(id, *at, *new_id),
+            WeaveAction::MergeNodeWithParent(id) => self.merge_with_parent(id).is_some(),
+            _ => self.apply_action(action),
+        })
+    }
+    fn apply_action(&mut self, action: &WeaveAction<K, IndependentNode<K, T, S>, T, M>) -> bool
+    where
+        T: Clone,
+        M: Clone,
+    {
+        match action {
+            WeaveAction::AddNode(node) => {
+                self.nodes.contains_key(&node.id) || self.add_node(node.clone())
+            }
+            WeaveAction::SetNodeActiveStatus {
+                id,
+                value,
+                alternate,
+            } => self.set_node_active_status(id, *value, *alternate),
+            WeaveAction::SetNodeActiveStatusInPlace { id, value } => {
+                self.set_node_active_status_in_place(id, *value)
+            }
+            WeaveAction::SetNodeBookmarkedStatus { id, value } => {
+                self.set_node_bookmarked_status(id, *value)
+            }
+            WeaveAction::RemoveNode(id) => {
+                !self.nodes.contains_key(id) || self.remove_node(id).is_some()
+            }
+            WeaveAction::RemoveAllNodes => {
+                self.remove_all_nodes();
+                true
+            }
+            WeaveAction::SetMetadata(metadata) => {
+                self.metadata = metadata.clone();
+                true
+            }
+            WeaveAction::SetNodeChildOrdering { parent, children } => {
+                let current = match parent {
+                    Some(parent) => self.nodes.get(parent).map(|node| &node.to),
+                    None => Some(&self.roots),
+                };
+
+                if current.is_some_and(|current| {
+                    current.len() == children.len()
+                        && children.iter().all(|child| current.contains(child))
+                }) {
+                    let mut id_mapping =
+                        HashMap::with_capacity_and_hasher(children.len(), S::default());
+                    id_mapping.extend(
+                        children
+                            .iter()
+                            .copied()
+                            .enumerate()
+                            .map(|(index, id)| (id, index)),
+                    );
+                    let compare =
+                        |a: &K, b: &K| id_mapping.get(a).unwrap().cmp(id_mapping.get(b).unwrap());
+
+                    match parent {
+                        Some(parent) => self.sort_node_children_by_id(parent, compare),
+                        None => {
+                            self.sort_roots_by_id(compare);
+                            true
+                        }
+                    }
+                } else {
+                    false
+                }
+            }
+            WeaveAction::SetBookmarkOrdering(ids) => {
+                if self.bookmarked.len() == ids.len()
+                    && ids.iter().all(|id| self.bookmarked.contains(id))
+                {
+                    let mut id_mapping = HashMap::with_capacity_and_hasher(ids.len(), S::default());
+                    id_mapping.extend(
+                        ids.iter()
+                            .copied()
+                            .enumerate()
+                            .map(|(index, id)| (id, index)),
+                    );
+
+                    self.sort_bookmarks_by_id(|a, b| {
+                        id_mapping.get(a).unwrap().cmp(id_mapping.get(b).unwrap())
+                    });
+                    true
+                } else {
+                    false
+                }
+            }
+            WeaveAction::MoveNode { id, new_parents } => {
+                crate::IndependentWeave::move_node(self, id, new_parents)
+            }
+            WeaveAction::SetNodeContent { id, contents } => {
+                crate::SemiIndependentWeave::get_contents_mut(self, id, |node_contents| {
+                    *node_contents = contents.clone()
+                })
+                .is_some()
+            }
+            WeaveAction::SplitNode { .. } | WeaveAction::MergeNodeWithParent(_) => false,
+        }
     }
     fn all_parents(
         &self,
@@ -437,6 +1659,41 @@ where
                 &mut self.scratchpad_list_2,
             );
 
+            // Active descendants which are not reachable through the chosen path belong to the previous thread
+            if let Some(tip) = self.scratchpad_list_2.last().copied() {
+                self.scratchpad_set.clear();
+                self.scratchpad_set
+                    .extend(self.scratchpad_list_2.iter().copied());
+
+                self.scratchpad_list.push(*id);
+                while let Some(item) = self.scratchpad_list.pop() {
+                    for parent in &self.nodes.get(&item).unwrap().from {
+                        if self.active.contains(parent) && self.scratchpad_set.insert(*parent) {
+                            self.scratchpad_list.push(*parent);
+                        }
+                    }
+                }
+
+                self.scratchpad_list.push(tip);
+                while let Some(item) = self.scratchpad_list.pop() {
+                    for child in &self.nodes.get(&item).unwrap().to {
+                        if self.active.contains(child) && self.scratchpad_set.insert(*child) {
+                            self.scratchpad_list.push(*child);
+                        }
+                    }
+                }
+
+                self.scratchpad_list
+                    .extend(self.active.difference(&self.scratchpad_set).copied());
+
+                for stale in self.scratchpad_list.drain(..) {
+                    self.active.remove(&stale);
+                    if let Some(node) = self.nodes.get_mut(&stale) {
+                        node.active = false;
+                    }
+                }
+            }
+
             for path_item in self.scratchpad_list_2.drain(..) {
                 if let Some(node) = self.nodes.get_mut(&path_item) {
                     node.active = true;
@@ -460,6 +1717,26 @@ where
 
         true
     }
+    fn deactivate_active_descendants(&mut self, id: &K) {
+        self.scratchpad_list.clear();
+        self.scratchpad_set.clear();
+
+        self.scratchpad_list.push(*id);
+        while let Some(item) = self.scratchpad_list.pop() {
+            for child in &self.nodes.get(&item).unwrap().to {
+                if self.active.contains(child) && self.scratchpad_set.insert(*child) {
+                    self.scratchpad_list.push(*child);
+                }
+            }
+        }
+
+        for descendant in self.scratchpad_set.drain() {
+            self.active.remove(&descendant);
+            if let Some(node) = self.nodes.get_mut(&descendant) {
+                node.active = false;
+            }
+        }
+    }
     pub(super) fn fix_orphaned_activations(&mut self) {
         self.scratchpad_list.clear();
         self.scratchpad_list_2.clear();
@@ -491,66 +1768,73 @@ where
                 node.active = false;
             }
         }
+
+        self.clear_scratchpads();
+    }
+    fn clear_scratchpads(&mut self) {
+        self.scratchpad_list.clear();
+        self.scratchpad_list_2.clear();
+        self.scratchpad_set.clear();
+        self.scratchpad_set_2.clear();
     }
     #[ensures(!self.nodes.contains_key(id))]
-    #[stacksafe]
     fn remove_node_unverified(&mut self, id: &K) -> Option<IndependentNode<K, T, S>> {
-        if let Some(node) = self.nodes.remove(id) {
-            self.roots.shift_remove(id);
-            self.bookmarked.shift_remove(id);
-            self.active.remove(id);
-            for parent in &node.from {
-                if let Some(parent) = self.nodes.get_mut(parent) {
-                    parent.to.shift_remove(&node.id);
-                }
-            }
-            for child in &node.to {
-                if let Some(child) = self.nodes.get_mut(child) {
-                    child.from.shift_remove(&node.id);
+        let mut worklist = Vec::new();
+        let node = self.detach_node(id, &mut worklist)?;
 
-                    let identifier = child.id;
-                    if child.from.is_empty() {
-                        self.remove_node_unverified(&identifier);
-                    }
-                }
-            }
-            Some(node)
-        } else {
-            None
+        while let Some(orphan) = worklist.pop() {
+            self.detach_node(&orphan, &mut worklist);
         }
+
+        Some(node)
     }
     #[ensures(!self.nodes.contains_key(id))]
-    #[stacksafe]
     fn remove_node_unverified_tracked(
         &mut self,
         id: &K,
         callback: &mut impl FnMut(IndependentNode<K, T, S>),
     ) -> bool {
-        if let Some(node) = self.nodes.remove(id) {
-            self.roots.shift_remove(id);
-            self.bookmarked.shift_remove(id);
-            self.active.remove(id);
-            for parent in &node.from {
-                if let Some(parent) = self.nodes.get_mut(parent) {
-                    parent.to.shift_remove(&node.id);
+        let mut worklist = Vec::new();
+
+        if let Some(node) = self.detach_node(id, &mut worklist) {
+            let mut removed = vec![node];
+
+            while let Some(orphan) = worklist.pop() {
+                if let Some(orphan) = self.detach_node(&orphan, &mut worklist) {
+                    removed.push(orphan);
                 }
             }
-            for child in &node.to {
-                if let Some(child) = self.nodes.get_mut(child) {
-                    child.from.shift_remove(&node.id);
 
-                    let identifier = child.id;
-                    if child.from.is_empty() {
-                        self.remove_node_unverified_tracked(&identifier, callback);
-                    }
-                }
+            for node in removed.into_iter().rev() {
+                callback(node);
             }
-            callback(node);
+
             true
         } else {
             false
         }
     }
+    fn detach_node(&mut self, id: &K, orphans: &mut Vec<K>) -> Option<IndependentNode<K, T, S>> {
+        let node = self.nodes.remove(id)?;
+        self.roots.shift_remove(id);
+        self.bookmarked.shift_remove(id);
+        self.active.remove(id);
+        for parent in &node.from {
+            if let Some(parent) = self.nodes.get_mut(parent) {
+                parent.to.shift_remove(&node.id);
+            }
+        }
+        for child in node.to.iter().rev() {
+            if let Some(child) = self.nodes.get_mut(child) {
+                child.from.shift_remove(&node.id);
+
+                if child.from.is_empty() {
+                    orphans.push(child.id);
+                }
+            }
+        }
+        Some(node)
+    }
 }
 
 impl<K, T, M, S> From<DependentWeave<K, T, M, S>> for IndependentWeave<K, T, M, S>
@@ -576,6 +1860,7 @@ where
                 active: node.active,
                 bookmarked: node.bookmarked,
                 contents: node.contents,
+                style: None,
             }));
         }
 
@@ -614,14 +1899,24 @@ where
     fn contains_active(&self, id: &K) -> bool {
         self.active.contains(id)
     }
+    fn is_on_active_thread(&self, id: &K) -> bool {
+        self.active.contains(id)
+    }
     fn contains_bookmark(&self, id: &K) -> bool {
         self.bookmarked.contains(id)
     }
+    fn count_active(&self) -> usize {
+        self.active.len()
+    }
+    fn count_bookmarked(&self) -> usize {
+        self.bookmarked.len()
+    }
     fn get_node(&self, id: &K) -> Option<&IndependentNode<K, T, S>> {
         self.nodes.get(id)
     }
     #[ensures(output.len() == self.nodes.len())]
     #[ensures(valid_ordered_nodes(&self.nodes, output))]
+    #[ensures(self.scratchpads_clean())]
     fn get_ordered_node_identifiers(&mut self, output: &mut Vec<K>) {
         output.clear();
         self.scratchpad_set.clear();
@@ -634,8 +1929,11 @@ where
                 &mut self.scratchpad_set,
             ); // Compiler limitation
         }
+
+        self.scratchpad_set.clear();
     }
     #[ensures(lacks_duplicates(output))]
+    #[ensures(self.scratchpads_clean())]
     fn get_ordered_node_identifiers_from(&mut self, id: &K, output: &mut Vec<K>) {
         output.clear();
         self.scratchpad_set.clear();
@@ -653,81 +1951,185 @@ where
                 &mut self.scratchpad_set,
             ); // Compiler limitation
         }
+
+        self.scratchpad_set.clear();
     }
     #[ensures(output.len() == self.active.len())]
     #[ensures(lacks_duplicates(output))]
     #[ensures(valid_thread(&self.nodes, output))]
+    #[ensures(self.scratchpads_clean())]
     fn get_active_thread(&mut self, output: &mut Vec<K>) {
-        output.clear();
-        self.scratchpad_list.clear();
-        self.scratchpad_set.clear();
-
-        for active_root in self
-            .roots
-            .iter()
-            .copied()
-            .filter(|root| self.active.contains(root))
-        {
-            build_thread_with_critera(
-                &self.nodes,
-                &|id| self.active.contains(id),
-                active_root,
-                &mut self.scratchpad_list,
-                &mut self.scratchpad_set,
-                output,
-            );
-        }
-
-        output.reverse();
+        build_active_thread(
+            &self.nodes,
+            &self.roots,
+            &self.active,
+            &mut self.scratchpad_list,
+            &mut self.scratchpad_set,
+            output,
+        );
     }
     #[ensures(lacks_duplicates(output))]
     #[ensures(valid_thread(&self.nodes, output))]
+    #[ensures(self.scratchpads_clean())]
     fn get_thread_from(&mut self, id: &K, output: &mut Vec<K>) {
-        output.clear();
-        self.scratchpad_set.clear();
-
-        build_thread_from(
+        build_thread_from_active(
             &self.nodes,
+            &self.roots,
             &self.active,
             *id,
-            output,
+            &mut self.scratchpad_list,
             &mut self.scratchpad_set,
+            output,
         );
+    }
+    #[ensures(ret.len() == self.active.len())]
+    #[ensures(lacks_duplicates(&ret))]
+    #[ensures(valid_thread(&self.nodes, &ret))]
+    fn active_thread_vec(&self) -> Vec<K> {
+        let mut output = Vec::with_capacity(self.active.len());
 
-        if let Some(last_thread_node) = output.last()
-            && !self.roots.contains(last_thread_node)
-        {
-            self.scratchpad_set.clear();
-            self.scratchpad_list.clear();
+        build_active_thread(
+            &self.nodes,
+            &self.roots,
+            &self.active,
+            &mut Vec::new(),
+            &mut HashSet::default(),
+            &mut output,
+        );
 
-            for active_root in self
-                .roots
-                .iter()
-                .copied()
-                .filter(|root| self.active.contains(root))
-            {
-                if build_thread_until(
-                    &self.nodes,
-                    &self.active,
-                    active_root,
-                    &HashSet::from_iter(
-                        self.nodes
-                            .get(last_thread_node)
-                            .unwrap()
-                            .from
-                            .iter()
-                            .copied()
-                            .filter(|parent| self.active.contains(parent)),
-                    ),
-                    &mut self.scratchpad_list,
-                    &mut self.scratchpad_set,
-                ) {
-                    break;
+        output
+    }
+    #[ensures(lacks_duplicates(&ret))]
+    #[ensures(valid_thread(&self.nodes, &ret))]
+    fn thread_from_vec(&self, id: &K) -> Vec<K> {
+        let mut output = Vec::new();
+
+        build_thread_from_active(
+            &self.nodes,
+            &self.roots,
+            &self.active,
+            *id,
+            &mut Vec::new(),
+            &mut HashSet::default(),
+            &mut output,
+        );
+
+        output
+    }
+    fn get_depth(&self, id: &K) -> Option<usize> {
+        node_depth(*id, self.nodes.len(), |id| {
+            self.nodes.get(id).map(|node| node.from.iter().copied())
+        })
+    }
+    fn subtree_size(&self, id: &K) -> Option<usize> {
+        subtree_size(*id, |id| {
+            self.nodes.get(id).map(|node| node.to.iter().copied())
+        })
+    }
+    fn ancestors(&self, id: &K) -> impl Iterator<Item = K> {
+        let mut queue: VecDeque<K> = self
+            .nodes
+            .get(id)
+            .map(|node| node.from.iter().copied().collect())
+            .unwrap_or_default();
+        let mut visited: HashSet<K, S> = queue.iter().copied().collect();
+
+        iter::from_fn(move || {
+            let ancestor = queue.pop_front()?;
+
+            if let Some(node) = self.nodes.get(&ancestor) {
+                queue.extend(
+                    node.from
+                        .iter()
+                        .copied()
+                        .filter(|parent| visited.insert(*parent)),
+                );
+            }
+
+            Some(ancestor)
+        })
+    }
+    fn descendants(&self, id: &K) -> impl Iterator<Item = K> {
+        let mut stack: Vec<K> = self
+            .nodes
+            .get(id)
+            .map(|node| node.to.iter().rev().copied().collect())
+            .unwrap_or_default();
+        let mut visited: HashSet<K, S> = HashSet::default();
+
+        iter::from_fn(move || {
+            loop {
+                let descendant = stack.pop()?;
+
+                if visited.insert(descendant) {
+                    if let Some(node) = self.nodes.get(&descendant) {
+                        stack.extend(node.to.iter().rev().copied());
+                    }
+
+                    return Some(descendant);
                 }
             }
+        })
+    }
+    fn leaves(&self) -> impl Iterator<Item = K> {
+        let mut stack: Vec<K> = self.roots.iter().rev().copied().collect();
+        let mut visited: HashSet<K, S> = HashSet::default();
 
-            output.extend(self.scratchpad_list.drain(..).rev());
-        }
+        iter::from_fn(move || {
+            loop {
+                let id = stack.pop()?;
+
+                if visited.insert(id)
+                    && let Some(node) = self.nodes.get(&id)
+                {
+                    if node.to.is_empty() {
+                        return Some(id);
+                    }
+
+                    stack.extend(node.to.iter().rev().copied());
+                }
+            }
+        })
+    }
+    fn get_all_threads(&self) -> impl Iterator<Item = Vec<K>> {
+        let mut roots = self.roots.iter().copied();
+        let mut path: Vec<(K, usize)> = Vec::new();
+
+        iter::from_fn(move || {
+            loop {
+                let Some((id, index)) = path.last_mut() else {
+                    path.push((roots.next()?, 0));
+                    continue;
+                };
+
+                let node = self.nodes.get(id).unwrap();
+
+                if node.to.is_empty() {
+                    let thread = path.iter().rev().map(|(id, _)| *id).collect();
+                    path.pop();
+
+                    return Some(thread);
+                }
+
+                if let Some(child) = node.to.get_index(*index).copied() {
+                    *index += 1;
+                    path.push((child, 0));
+                } else {
+                    path.pop();
+                }
+            }
+        })
+    }
+    fn search_contents(&self, predicate: impl Fn(&T) -> bool) -> impl Iterator<Item = K> {
+        self.nodes
+            .values()
+            .filter(move |node| predicate(&node.contents))
+            .map(|node| node.id)
+    }
+    fn get_sibling_index(&self, id: &K) -> Option<(usize, usize)> {
+        let siblings = self.sibling_set(id)?;
+
+        Some((siblings.get_index_of(id)?, siblings.len()))
     }
     #[ensures(!ret || old(self.nodes.len()) + 1 == self.nodes.len())]
     #[ensures(!ret || old(!self.nodes.contains_key(&node.id)))]
@@ -801,13 +2203,11 @@ where
                 .filter_map(|child| self.nodes.get(child))
                 .find(|child| child.active)
         {
-            let child_id = active_child.id;
-
             if (!alternate && active_child.from.len() == 1)
                 || (alternate && active_child.from.len() > 1)
             {
                 let result = self.update_node_activity_in_place(id, true);
-                self.update_node_activity_in_place(&child_id, false);
+                self.deactivate_active_descendants(id);
 
                 result
             } else {
@@ -910,6 +2310,7 @@ where
 {
     #[ensures(output.len() == self.nodes.len())]
     #[ensures(valid_ordered_nodes(&self.nodes, output))]
+    #[ensures(self.scratchpads_clean())]
     fn get_ordered_node_identifiers_reversed_children(&mut self, output: &mut Vec<K>) {
         output.clear();
         self.scratchpad_set.clear();
@@ -922,8 +2323,11 @@ where
                 &mut self.scratchpad_set,
             ); // Compiler limitation
         }
+
+        self.scratchpad_set.clear();
     }
     #[ensures(lacks_duplicates(output))]
+    #[ensures(self.scratchpads_clean())]
     fn get_ordered_node_identifiers_from_reversed_children(&mut self, id: &K, output: &mut Vec<K>) {
         output.clear();
         self.scratchpad_set.clear();
@@ -941,6 +2345,8 @@ where
                 &mut self.scratchpad_set,
             ); // Compiler limitation
         }
+
+        self.scratchpad_set.clear();
     }
     #[ensures(old(self.nodes.len()) == self.nodes.len())]
     #[ensures(ret == self.nodes.contains_key(id))]
@@ -992,6 +2398,34 @@ where
     fn sort_roots_by_id(&mut self, compare: impl FnMut(&K, &K) -> Ordering) {
         self.roots.sort_by(compare);
     }
+    #[ensures(old(self.nodes.len()) == self.nodes.len())]
+    #[ensures(old(self.nodes.get(parent).map(|node| node.to.len())) == self.nodes.get(parent).map(|node| node.to.len()))]
+    #[invariant(self.validate())]
+    fn move_child_to_index(&mut self, parent: &K, child: &K, index: usize) -> bool {
+        if let Some(node) = self.nodes.get_mut(parent)
+            && let Some(current) = node.to.get_index_of(child)
+        {
+            node.to.move_index(current, index.min(node.to.len() - 1));
+
+            true
+        } else {
+            false
+        }
+    }
+    #[ensures(old(self.nodes.len()) == self.nodes.len())]
+    #[ensures(old(self.roots.len()) == self.roots.len())]
+    #[ensures(ret == self.roots.contains(id))]
+    #[invariant(self.validate())]
+    fn move_root_to_index(&mut self, id: &K, index: usize) -> bool {
+        if let Some(current) = self.roots.get_index_of(id) {
+            self.roots
+                .move_index(current, index.min(self.roots.len() - 1));
+
+            true
+        } else {
+            false
+        }
+    }
     #[ensures(old(self.bookmarked.len()) == self.bookmarked.len())]
     #[invariant(self.validate())]
     fn sort_bookmarks_by(
@@ -1019,68 +2453,165 @@ where
     fn active(&self) -> &Self::Active {
         &self.active
     }
-}
-
-impl<K, T, M, S> DiscreteWeave<K, IndependentNode<K, T, S>, T> for IndependentWeave<K, T, M, S>
-where
-    K: Hash + Copy + Eq,
-    T: IndependentContents + DiscreteContents,
-    S: BuildHasher + Default + Clone,
-{
-    #[ensures(!ret || old(self.nodes.len()) + 1 == self.nodes.len())]
+}
+
+impl<K, T, M, S> DiscreteWeave<K, IndependentNode<K, T, S>, T> for IndependentWeave<K, T, M, S>
+where
+    K: Hash + Copy + Eq,
+    T: IndependentContents + DiscreteContents,
+    S: BuildHasher + Default + Clone,
+{
+    #[ensures(ret.is_err() || old(self.nodes.len()) + 1 == self.nodes.len())]
+    #[ensures(ret.is_err() || self.nodes.contains_key(id))]
+    #[ensures(ret.is_err() || self.nodes.contains_key(&new_id))]
+    #[ensures(ret.is_err() || old(!self.nodes.contains_key(&new_id)))]
+    #[ensures(ret.is_ok() || old(self.nodes.len()) == self.nodes.len())]
+    #[ensures(ret.is_ok() || old(self.active.clone()) == self.active)]
+    #[ensures(ret.is_ok() || old(self.bookmarked.clone()) == self.bookmarked)]
+    #[invariant(self.validate())]
+    fn try_split_node(&mut self, id: &K, at: usize, new_id: K) -> Result<(), SplitError> {
+        let Some(node) = self.nodes.get(id) else {
+            return Err(SplitError::NodeNotFound);
+        };
+
+        if self.nodes.contains_key(&new_id) || *id == new_id {
+            return Err(SplitError::IdentifierInUse);
+        }
+        if !self.under_max_size() {
+            return Err(SplitError::CapacityExceeded);
+        }
+        if !node.contents.can_split_at(at) {
+            return Err(SplitError::InvalidIndex);
+        }
+
+        let mut node = self.nodes.remove(id).unwrap();
+
+        match node.contents.split(at) {
+            DiscreteContentResult::Two(left, right) => {
+                let left_node = IndependentNode {
+                    id: node.id,
+                    from: node.from,
+                    to: IndexSet::from_iter([new_id]),
+                    active: node.active,
+                    bookmarked: node.bookmarked,
+                    contents: left,
+                    style: node.style,
+                };
+
+                node.from = IndexSet::from_iter([node.id]);
+                node.id = new_id;
+                node.contents = right;
+                node.active = false;
+                node.bookmarked = false;
+
+                for child in node.to.iter() {
+                    let child = self.nodes.get_mut(child).unwrap();
+
+                    if let Some(index) = child.from.get_index_of(&left_node.id) {
+                        if child.from.replace_index(index, node.id).is_err() {
+                            child.from.shift_remove_index(index);
+                        }
+                    } else {
+                        child.from.insert(node.id);
+                    }
+                    if child.active && left_node.active {
+                        node.active = true;
+                        self.active.insert(node.id);
+                    }
+                }
+
+                self.nodes.insert(left_node.id, left_node);
+                self.nodes.insert(node.id, node);
+
+                Ok(())
+            }
+            DiscreteContentResult::One(content) => {
+                node.contents = content;
+                self.nodes.insert(node.id, node);
+                Err(SplitError::SplitFailed)
+            }
+        }
+    }
+    #[ensures(!ret || old(self.nodes.len()) + new_ids.len() == self.nodes.len())]
     #[ensures(!ret || self.nodes.contains_key(id))]
-    #[ensures(!ret || self.nodes.contains_key(&new_id))]
-    #[ensures(!ret || old(!self.nodes.contains_key(&new_id)))]
+    #[ensures(!ret || new_ids.iter().all(|new_id| self.nodes.contains_key(new_id)))]
     #[ensures(ret || old(self.nodes.len()) == self.nodes.len())]
     #[ensures(ret || old(self.active.clone()) == self.active)]
     #[ensures(ret || old(self.bookmarked.clone()) == self.bookmarked)]
     #[invariant(self.validate())]
-    fn split_node(&mut self, id: &K, at: usize, new_id: K) -> bool {
-        if self.nodes.contains_key(&new_id) || *id == new_id || !self.under_max_size() {
+    fn split_node_multi(&mut self, id: &K, ats: &[usize], new_ids: &[K]) -> bool {
+        if ats.len() != new_ids.len()
+            || !ats.is_sorted_by(|a, b| a < b)
+            || new_ids
+                .iter()
+                .any(|new_id| new_id == id || self.nodes.contains_key(new_id))
+            || new_ids.iter().collect::<HashSet<_>>().len() != new_ids.len()
+            || (self.nodes.len() as u64 + new_ids.len() as u64) > (i32::MAX as u64)
+        {
             return false;
         }
 
         if let Some(mut node) = self.nodes.remove(id) {
-            match node.contents.split(at) {
-                DiscreteContentResult::Two(left, right) => {
-                    let left_node = IndependentNode {
-                        id: node.id,
-                        from: node.from,
-                        to: IndexSet::from_iter([new_id]),
-                        active: node.active,
-                        bookmarked: node.bookmarked,
-                        contents: left,
-                    };
-
-                    node.from = IndexSet::from_iter([node.id]);
-                    node.id = new_id;
-                    node.contents = right;
-                    node.active = false;
-                    node.bookmarked = false;
-
-                    for child in node.to.iter() {
+            match split_contents(node.contents, ats) {
+                Ok(pieces) => {
+                    let identifiers: Vec<K> =
+                        iter::once(node.id).chain(new_ids.iter().copied()).collect();
+                    let last = *identifiers.last().unwrap();
+                    let mut children = mem::take(&mut node.to);
+                    let mut continues_active = false;
+
+                    for child in children.iter() {
                         let child = self.nodes.get_mut(child).unwrap();
 
-                        if let Some(index) = child.from.get_index_of(&left_node.id) {
-                            if child.from.replace_index(index, node.id).is_err() {
+                        if let Some(index) = child.from.get_index_of(&node.id) {
+                            if child.from.replace_index(index, last).is_err() {
                                 child.from.shift_remove_index(index);
                             }
                         } else {
-                            child.from.insert(node.id);
+                            child.from.insert(last);
                         }
-                        if child.active && left_node.active {
-                            node.active = true;
-                            self.active.insert(node.id);
+                        if child.active && node.active {
+                            continues_active = true;
                         }
                     }
 
-                    self.nodes.insert(left_node.id, left_node);
-                    self.nodes.insert(node.id, node);
+                    for (index, contents) in pieces.into_iter().enumerate() {
+                        let is_first = index == 0;
+                        let active = if is_first {
+                            node.active
+                        } else {
+                            continues_active
+                        };
+
+                        if active && !is_first {
+                            self.active.insert(identifiers[index]);
+                        }
+
+                        self.nodes.insert(
+                            identifiers[index],
+                            IndependentNode {
+                                id: identifiers[index],
+                                from: if is_first {
+                                    mem::take(&mut node.from)
+                                } else {
+                                    IndexSet::from_iter([identifiers[index - 1]])
+                                },
+                                to: match identifiers.get(index + 1) {
+                                    Some(next) => IndexSet::from_iter([*next]),
+                                    None => mem::take(&mut children),
+                                },
+                                active,
+                                bookmarked: is_first && node.bookmarked,
+                                contents,
+                                style: node.style,
+                            },
+                        );
+                    }
 
                     true
                 }
-                DiscreteContentResult::One(content) => {
-                    node.contents = content;
+                Err(contents) => {
+                    node.contents = contents;
                     self.nodes.insert(node.id, node);
                     false
                 }
@@ -1168,6 +2699,47 @@ where
             .get_mut(id)
             .map(|node| callback(&mut node.contents))
     }
+    #[ensures(ret == self.nodes.contains_key(id))]
+    #[ensures(self.scratchpads_clean())]
+    #[invariant(self.validate())]
+    fn with_node_mut(
+        &mut self,
+        id: &K,
+        callback: impl FnOnce(&mut IndependentNode<K, T, S>),
+    ) -> bool {
+        let Some(node) = self.nodes.get_mut(id) else {
+            return false;
+        };
+        let (from, to, active, bookmarked) = (
+            mem::take(&mut node.from),
+            mem::take(&mut node.to),
+            node.active,
+            node.bookmarked,
+        );
+
+        callback(node);
+
+        let new_active = node.active;
+        node.id = *id;
+        node.from = from;
+        node.to = to;
+        node.active = active;
+
+        // The bookmark flag only needs to agree with the set of bookmarks.
+        if node.bookmarked != bookmarked {
+            if node.bookmarked {
+                self.bookmarked.insert(*id);
+            } else {
+                self.bookmarked.shift_remove(id);
+            }
+        }
+        // Writing the active flag directly could leave the active nodes without a single thread, so activity changes are made in place instead.
+        if new_active != active {
+            self.update_node_activity_in_place(id, new_active);
+        }
+
+        true
+    }
 }
 
 impl<K, T, M, S> DeduplicatableWeave<K, IndependentNode<K, T, S>, T>
@@ -1190,6 +2762,12 @@ where
                 })
         })
     }
+    fn find_duplicate_groups(&self) -> impl Iterator<Item = Vec<K>>
+    where
+        T: HashableContents,
+    {
+        duplicate_groups(self.nodes.values().map(|node| (node.id, &node.contents)))
+    }
 }
 
 impl<K, T, M, S> crate::IndependentWeave<K, IndependentNode<K, T, S>, T>
@@ -1219,13 +2797,14 @@ where
             return false;
         }
 
-        if let Some(node) = self.nodes.get_mut(id) {
-            for child in &node.to {
-                if new_parents.contains(child) {
-                    return false;
-                }
-            }
+        if new_parents
+            .iter()
+            .any(|new_parent| self.is_ancestor_of(id, new_parent))
+        {
+            return false;
+        }
 
+        if let Some(node) = self.nodes.get_mut(id) {
             let old_parents = mem::take(&mut node.from);
 
             for old_parent in &old_parents {
@@ -1306,6 +2885,34 @@ where
     }
 }
 
+#[cfg(feature = "rkyv")]
+impl<K, T, M, S> ArchivedIndependentWeave<K, T, M, S>
+where
+    K: Archive + Hash + Copy + Eq,
+    <K as Archive>::Archived: Hash + Eq,
+    T: Archive + IndependentContents,
+    M: Archive,
+    S: BuildHasher + Default + Clone,
+{
+    /// Deserializes the archived weave into an owned [`IndependentWeave`], without requiring access to the buffer the weave was archived into.
+    ///
+    /// The owned weave's internal scratchpads are preallocated to match the number of nodes in the weave.
+    pub fn to_owned_weave(&self) -> Result<IndependentWeave<K, T, M, S>, rancor::Error>
+    where
+        Self: Deserialize<IndependentWeave<K, T, M, S>, Strategy<Pool, rancor::Error>>,
+    {
+        let mut weave: IndependentWeave<K, T, M, S> = rkyv::deserialize(self)?;
+        let capacity = weave.nodes.len();
+
+        weave.scratchpad_list.reserve(capacity);
+        weave.scratchpad_list_2.reserve(capacity);
+        weave.scratchpad_set.reserve(capacity);
+        weave.scratchpad_set_2.reserve(capacity);
+
+        Ok(weave)
+    }
+}
+
 #[cfg(feature = "rkyv")]
 impl<K, K2, T, T2, M, M2, S>
     ArchivedWeave<K::Archived, ArchivedIndependentNode<K, T, S>, T::Archived>
@@ -1345,6 +2952,9 @@ where
     fn contains_bookmark(&self, id: &K::Archived) -> bool {
         self.bookmarked.contains(id)
     }
+    fn count_bookmarked(&self) -> usize {
+        self.bookmarked.len()
+    }
     fn get_node(&self, id: &K::Archived) -> Option<&ArchivedIndependentNode<K, T, S>> {
         self.nodes.get(id)
     }
@@ -1429,6 +3039,46 @@ where
             output.extend(alternate_thread_list.into_iter().rev());
         }
     }
+    fn get_depth(&self, id: &K::Archived) -> Option<usize> {
+        node_depth(*id, self.nodes.len(), |id| {
+            self.nodes.get(id).map(|node| node.from.iter().copied())
+        })
+    }
+    fn subtree_size(&self, id: &K::Archived) -> Option<usize> {
+        subtree_size(*id, |id| {
+            self.nodes.get(id).map(|node| node.to.iter().copied())
+        })
+    }
+    fn search_contents(
+        &self,
+        predicate: impl Fn(&T::Archived) -> bool,
+    ) -> impl Iterator<Item = K::Archived> {
+        self.nodes
+            .values()
+            .filter(move |node| predicate(&node.contents))
+            .map(|node| node.id)
+    }
+    fn leaves(&self) -> impl Iterator<Item = K::Archived> {
+        let mut stack: Vec<K::Archived> = self.roots.iter().copied().collect();
+        let mut visited = HashSet::with_capacity(self.len());
+        stack.reverse();
+
+        iter::from_fn(move || {
+            loop {
+                let id = stack.pop()?;
+
+                if visited.insert(id)
+                    && let Some(node) = self.nodes.get(&id)
+                {
+                    if node.to.is_empty() {
+                        return Some(id);
+                    }
+
+                    stack.extend(node.to.iter().collect::<Vec<_>>().into_iter().rev());
+                }
+            }
+        })
+    }
 }
 
 #[cfg(feature = "rkyv")]
@@ -1498,7 +3148,90 @@ where
     }
 }
 
-#[stacksafe]
+fn build_active_thread<K, T, S>(
+    nodes: &HashMap<K, IndependentNode<K, T, S>, S>,
+    roots: &IndexSet<K, S>,
+    active: &HashSet<K, S>,
+    scratchpad_list: &mut Vec<K>,
+    scratchpad_set: &mut HashSet<K, S>,
+    output: &mut Vec<K>,
+) where
+    K: Hash + Copy + Eq,
+    T: IndependentContents,
+    S: BuildHasher + Default + Clone,
+{
+    output.clear();
+    scratchpad_list.clear();
+    scratchpad_set.clear();
+
+    for active_root in roots.iter().copied().filter(|root| active.contains(root)) {
+        build_thread_with_critera(
+            nodes,
+            &|id| active.contains(id),
+            active_root,
+            scratchpad_list,
+            scratchpad_set,
+            output,
+        );
+    }
+
+    scratchpad_list.clear();
+    scratchpad_set.clear();
+
+    output.reverse();
+}
+
+fn build_thread_from_active<K, T, S>(
+    nodes: &HashMap<K, IndependentNode<K, T, S>, S>,
+    roots: &IndexSet<K, S>,
+    active: &HashSet<K, S>,
+    id: K,
+    scratchpad_list: &mut Vec<K>,
+    scratchpad_set: &mut HashSet<K, S>,
+    output: &mut Vec<K>,
+) where
+    K: Hash + Copy + Eq,
+    T: IndependentContents,
+    S: BuildHasher + Default + Clone,
+{
+    output.clear();
+    scratchpad_set.clear();
+
+    build_thread_from(nodes, active, id, output, scratchpad_set);
+
+    if let Some(last_thread_node) = output.last()
+        && !roots.contains(last_thread_node)
+    {
+        scratchpad_set.clear();
+        scratchpad_list.clear();
+
+        for active_root in roots.iter().copied().filter(|root| active.contains(root)) {
+            if build_thread_until(
+                nodes,
+                active,
+                active_root,
+                &HashSet::from_iter(
+                    nodes
+                        .get(last_thread_node)
+                        .unwrap()
+                        .from
+                        .iter()
+                        .copied()
+                        .filter(|parent| active.contains(parent)),
+                ),
+                scratchpad_list,
+                scratchpad_set,
+            ) {
+                break;
+            }
+        }
+
+        output.extend(scratchpad_list.drain(..).rev());
+    }
+
+    scratchpad_set.clear();
+}
+
 fn build_thread_with_critera<K, T, S>(
     nodes: &HashMap<K, IndependentNode<K, T, S>, S>,
     criteria: &impl Fn(&K) -> bool,
@@ -1511,39 +3244,46 @@ fn build_thread_with_critera<K, T, S>(
     T: IndependentContents,
     S: BuildHasher + Default + Clone,
 {
-    if let Some(node) = nodes.get(&id)
-        && node
-            .from
-            .iter()
-            .filter(|parent| criteria(parent))
-            .all(|parent| scratchpad_set.contains(parent))
-        && scratchpad_set.insert(id)
-    {
-        scratchpad_list.push(id);
+    let mut stack = Vec::new();
+    let mut next = Some(id);
+
+    loop {
+        if let Some(id) = next.take()
+            && let Some(node) = nodes.get(&id)
+            && node
+                .from
+                .iter()
+                .filter(|parent| criteria(parent))
+                .all(|parent| scratchpad_set.contains(parent))
+            && scratchpad_set.insert(id)
+        {
+            scratchpad_list.push(id);
+
+            if scratchpad_list.len() > thread_list.len() {
+                thread_list.clone_from(scratchpad_list);
+            }
 
-        if scratchpad_list.len() > thread_list.len() {
-            thread_list.clone_from(scratchpad_list);
+            stack.push((node, 0));
         }
 
-        for child in node.to.iter().copied() {
+        let Some((node, index)) = stack.last_mut() else {
+            break;
+        };
+
+        if let Some(child) = node.to.get_index(*index).copied() {
+            *index += 1;
+
             if criteria(&child) {
-                build_thread_with_critera(
-                    nodes,
-                    criteria,
-                    child,
-                    scratchpad_list,
-                    scratchpad_set,
-                    thread_list,
-                );
+                next = Some(child);
             }
+        } else {
+            scratchpad_list.pop();
+            scratchpad_set.remove(&node.id);
+            stack.pop();
         }
-
-        scratchpad_list.pop();
-        scratchpad_set.remove(&id);
     }
 }
 
-#[stacksafe]
 fn build_thread_until<K, T, S>(
     nodes: &HashMap<K, IndependentNode<K, T, S>, S>,
     active: &HashSet<K, S>,
@@ -1557,38 +3297,46 @@ where
     T: IndependentContents,
     S: BuildHasher + Default + Clone,
 {
-    if let Some(node) = nodes.get(&id)
-        && node
-            .from
-            .iter()
-            .filter(|parent| active.contains(*parent))
-            .all(|parent| thread_set.contains(parent))
-        && thread_set.insert(id)
-    {
-        thread_list.push(id);
+    let mut stack = Vec::new();
+    let mut next = Some(id);
+
+    loop {
+        if let Some(id) = next.take()
+            && let Some(node) = nodes.get(&id)
+            && node
+                .from
+                .iter()
+                .filter(|parent| active.contains(*parent))
+                .all(|parent| thread_set.contains(parent))
+            && thread_set.insert(id)
+        {
+            thread_list.push(id);
 
-        if !stop_at.contains(&id) {
-            for child in node.to.iter().copied() {
-                if active.contains(&child)
-                    && build_thread_until(nodes, active, child, stop_at, thread_list, thread_set)
-                {
-                    return true;
-                }
+            if stop_at.contains(&id) {
+                return true;
             }
 
-            thread_list.pop();
-            thread_set.remove(&id);
+            stack.push((node, 0));
+        }
 
-            false
+        let Some((node, index)) = stack.last_mut() else {
+            return false;
+        };
+
+        if let Some(child) = node.to.get_index(*index).copied() {
+            *index += 1;
+
+            if active.contains(&child) {
+                next = Some(child);
+            }
         } else {
-            true
+            thread_list.pop();
+            thread_set.remove(&node.id);
+            stack.pop();
         }
-    } else {
-        false
     }
 }
 
-#[stacksafe]
 fn build_thread_from<K, T, S>(
     nodes: &HashMap<K, IndependentNode<K, T, S>, S>,
     active: &HashSet<K, S>,
@@ -1600,7 +3348,11 @@ fn build_thread_from<K, T, S>(
     T: IndependentContents,
     S: BuildHasher + Default + Clone,
 {
-    if let Some(node) = nodes.get(&id) {
+    let mut current = Some(id);
+
+    while let Some(id) = current
+        && let Some(node) = nodes.get(&id)
+    {
         thread_list.push(id);
         thread_set.insert(id);
 
@@ -1608,10 +3360,81 @@ fn build_thread_from<K, T, S>(
             return;
         }
 
-        if let Some(parent) = node.from.first().copied() {
-            build_thread_from(nodes, active, parent, thread_list, thread_set);
-        }
+        current = node.from.first().copied();
+    }
+}
+
+const ACTIVE_WALK_DEPTH_LIMIT: usize = 512;
+
+struct ThreadFrame<'a, K> {
+    id: K,
+    parent: Option<&'a ThreadFrame<'a, K>>,
+}
+
+impl<K> ThreadFrame<'_, K>
+where
+    K: Eq,
+{
+    fn contains(&self, id: &K) -> bool {
+        iter::successors(Some(self), |frame| frame.parent).any(|frame| frame.id == *id)
+    }
+}
+
+fn next_active_child<'a, K, T, S>(
+    nodes: &'a HashMap<K, IndependentNode<K, T, S>, S>,
+    active: &HashSet<K, S>,
+    node: &IndependentNode<K, T, S>,
+    visited: impl Fn(&K) -> bool,
+) -> Option<&'a IndependentNode<K, T, S>>
+where
+    K: Hash + Copy + Eq,
+    T: IndependentContents,
+    S: BuildHasher + Default + Clone,
+{
+    node.to
+        .iter()
+        .filter(|child| active.contains(*child))
+        .filter_map(|child| nodes.get(child))
+        .find(|child| {
+            child
+                .from
+                .iter()
+                .filter(|parent| active.contains(*parent))
+                .all(&visited)
+        })
+}
+
+fn for_each_active_from<'a, K, T, S>(
+    nodes: &'a HashMap<K, IndependentNode<K, T, S>, S>,
+    active: &HashSet<K, S>,
+    node: &'a IndependentNode<K, T, S>,
+    thread: Option<&ThreadFrame<'_, K>>,
+    depth: usize,
+    f: &mut impl FnMut(&'a IndependentNode<K, T, S>),
+) -> bool
+where
+    K: Hash + Copy + Eq,
+    T: IndependentContents,
+    S: BuildHasher + Default + Clone,
+{
+    if depth > ACTIVE_WALK_DEPTH_LIMIT {
+        return false;
+    }
+
+    let frame = ThreadFrame {
+        id: node.id,
+        parent: thread,
+    };
+
+    if let Some(child) = next_active_child(nodes, active, node, |parent| frame.contains(parent))
+        && !for_each_active_from(nodes, active, child, Some(&frame), depth + 1, f)
+    {
+        return false;
     }
+
+    f(node);
+
+    true
 }
 
 #[stacksafe]
@@ -1674,7 +3497,6 @@ fn build_longest_thread_from<K, T, S>(
 }
 
 #[cfg(feature = "rkyv")]
-#[stacksafe]
 fn build_thread_archived<K, K2, T, T2, S>(
     nodes: &ArchivedHashMap<K::Archived, ArchivedIndependentNode<K, T, S>>,
     active: &ArchivedHashSet<K::Archived>,
@@ -1688,40 +3510,47 @@ fn build_thread_archived<K, K2, T, T2, S>(
     T: Archive<Archived = T2> + IndependentContents,
     S: BuildHasher + Default + Clone,
 {
-    if let Some(node) = nodes.get(&id)
-        && node
-            .from
-            .iter()
-            .filter(|parent| active.contains(*parent))
-            .all(|parent| thread_set.contains(parent))
-        && thread_set.insert(id)
-    {
-        scratchpad_list.push(id);
+    let mut stack = Vec::new();
+    let mut next = Some(id);
+
+    loop {
+        if let Some(id) = next.take()
+            && let Some(node) = nodes.get(&id)
+            && node
+                .from
+                .iter()
+                .filter(|parent| active.contains(*parent))
+                .all(|parent| thread_set.contains(parent))
+            && thread_set.insert(id)
+        {
+            scratchpad_list.push(id);
+
+            if scratchpad_list.len() > thread_list.len() {
+                thread_list.clone_from(scratchpad_list);
+            }
 
-        if scratchpad_list.len() > thread_list.len() {
-            thread_list.clone_from(scratchpad_list);
+            stack.push((id, node, 0));
         }
 
-        for child in node.to.iter().copied() {
+        let Some((id, node, index)) = stack.last_mut() else {
+            break;
+        };
+
+        if let Some(child) = node.to.get_index(*index).copied() {
+            *index += 1;
+
             if active.contains(&child) {
-                build_thread_archived(
-                    nodes,
-                    active,
-                    child,
-                    scratchpad_list,
-                    thread_set,
-                    thread_list,
-                );
+                next = Some(child);
             }
+        } else {
+            scratchpad_list.pop();
+            thread_set.remove(id);
+            stack.pop();
         }
-
-        scratchpad_list.pop();
-        thread_set.remove(&id);
     }
 }
 
 #[cfg(feature = "rkyv")]
-#[stacksafe]
 fn build_thread_archived_until<K, K2, T, T2, S>(
     nodes: &ArchivedHashMap<K::Archived, ArchivedIndependentNode<K, T, S>>,
     active: &ArchivedHashSet<K::Archived>,
@@ -1736,45 +3565,47 @@ where
     T: Archive<Archived = T2> + IndependentContents,
     S: BuildHasher + Default + Clone,
 {
-    if let Some(node) = nodes.get(&id)
-        && node
-            .from
-            .iter()
-            .filter(|parent| active.contains(*parent))
-            .all(|parent| thread_set.contains(parent))
-        && thread_set.insert(id)
-    {
-        thread_list.push(id);
+    let mut stack = Vec::new();
+    let mut next = Some(id);
+
+    loop {
+        if let Some(id) = next.take()
+            && let Some(node) = nodes.get(&id)
+            && node
+                .from
+                .iter()
+                .filter(|parent| active.contains(*parent))
+                .all(|parent| thread_set.contains(parent))
+            && thread_set.insert(id)
+        {
+            thread_list.push(id);
 
-        if !stop_at.contains(&id) {
-            for child in node.to.iter().copied() {
-                if active.contains(&child)
-                    && build_thread_archived_until(
-                        nodes,
-                        active,
-                        child,
-                        stop_at,
-                        thread_list,
-                        thread_set,
-                    )
-                {
-                    return true;
-                }
+            if stop_at.contains(&id) {
+                return true;
             }
 
-            thread_list.pop();
-            thread_set.remove(&id);
-            false
+            stack.push((id, node, 0));
+        }
+
+        let Some((id, node, index)) = stack.last_mut() else {
+            return false;
+        };
+
+        if let Some(child) = node.to.get_index(*index).copied() {
+            *index += 1;
+
+            if active.contains(&child) {
+                next = Some(child);
+            }
         } else {
-            true
+            thread_list.pop();
+            thread_set.remove(id);
+            stack.pop();
         }
-    } else {
-        false
     }
 }
 
 #[cfg(feature = "rkyv")]
-#[stacksafe]
 fn build_thread_from_archived<K, K2, T, T2, S>(
     nodes: &ArchivedHashMap<K::Archived, ArchivedIndependentNode<K, T, S>>,
     active: &ArchivedHashSet<K::Archived>,
@@ -1787,7 +3618,11 @@ fn build_thread_from_archived<K, K2, T, T2, S>(
     T: Archive<Archived = T2> + IndependentContents,
     S: BuildHasher + Default + Clone,
 {
-    if let Some(node) = nodes.get(&id) {
+    let mut current = Some(id);
+
+    while let Some(id) = current
+        && let Some(node) = nodes.get(&id)
+    {
         thread_list.push(id);
         thread_set.insert(id);
 
@@ -1795,9 +3630,7 @@ fn build_thread_from_archived<K, K2, T, T2, S>(
             return;
         }
 
-        if let Some(parent) = node.from.get_index(0).copied() {
-            build_thread_from_archived(nodes, active, parent, thread_list, thread_set);
-        }
+        current = node.from.get_index(0).copied();
     }
 }
 
@@ -17,11 +17,14 @@ pub mod versioning;
 
 use std::{
     cmp::Ordering,
-    collections::HashSet,
-    hash::{BuildHasher, Hash},
+    collections::{HashMap, HashSet},
+    hash::{BuildHasher, Hash, RandomState},
+    mem,
     ops::Index,
 };
 
+use indexmap::IndexSet;
+
 pub use contracts;
 pub use indexmap;
 pub use stacksafe;
@@ -71,15 +74,37 @@ where
     fn is_bookmarked(&self) -> bool;
 }
 
+/// A [`Node`] which knows when it was created.
+pub trait TimestampedNode<K, T>: Node<K, T>
+where
+    K: Hash + Copy + Eq,
+{
+    /// Returns the time at which the node was created, or `None` if it is unknown.
+    ///
+    /// The unit and epoch of timestamps are chosen by the implementation, but must be consistent across every node within a [`Weave`].
+    fn created_at(&self) -> Option<u64>;
+}
+
 /// [`Node`] contents which can be split apart or merged together.
 pub trait DiscreteContents: Sized {
     /// Splits the item at specified index.
     ///
-    /// If splitting the item fails, the original contents are returned.
+    /// If splitting the item fails, the original contents are returned. Splitting must not fail at an index accepted by [`DiscreteContents::can_split_at`], and the two halves of a successful split must merge back into the original item using [`DiscreteContents::merge`].
     fn split(self, at: usize) -> DiscreteContentResult<Self>;
+    /// Returns `true` if the item can be split at the specified index.
+    ///
+    /// [`DiscreteWeave`] implementations check this before calling [`DiscreteContents::split`], allowing items to reject indexes which would produce invalid contents (such as indexes within a multi-byte UTF-8 character). By default, all indexes are accepted.
+    ///
+    /// Returning `true` guarantees that [`DiscreteContents::split`] succeeds at the index. When splitting at several indexes, each index is checked against the contents remaining after the previous split, and the pieces which were already split off are merged back together if an index is rejected; this relies on [`DiscreteContents::split`] and [`DiscreteContents::merge`] being inverses.
+    fn can_split_at(&self, at: usize) -> bool {
+        let _ = at;
+        true
+    }
     /// Merges two items together.
     ///
     /// If merging the two items fails, the original contents are returned in the order they were specified in.
+    ///
+    /// Merging must undo splitting: for all `left` and `right` returned by a successful [`DiscreteContents::split`], `left.merge(right)` must succeed and produce contents equivalent to the original item. [`DiscreteWeave::split_node_multi`] relies on this to restore contents when a later index is rejected.
     fn merge(self, value: Self) -> DiscreteContentResult<Self>;
 }
 
@@ -101,6 +126,12 @@ pub trait DeduplicatableContents {
     fn is_duplicate_of(&self, other: &Self) -> bool;
 }
 
+/// [`DeduplicatableContents`] which can be hashed, allowing duplicates to be found without comparing every pair of items.
+///
+/// Hashing must be consistent with deduplication:
+/// For all `a` and `b`, `a.is_duplicate_of(b)` implies that `a` and `b` produce the same hash.
+pub trait HashableContents: DeduplicatableContents + Hash {}
+
 /// A document linking together multiple [`Node`] objects without cyclical links.
 pub trait Weave<K, N, T>
 where
@@ -130,8 +161,18 @@ where
     ///
     /// The meaning of this value can depend on the underlying Weave implementation.
     fn contains_active(&self, id: &K) -> bool;
+    /// Returns `true` if the node with the specified identifier is part of the active thread (see [`Weave::get_active_thread`]).
+    ///
+    /// Unlike [`Weave::contains_active`], the meaning of this value does not depend on the underlying Weave implementation. In Weave implementations where only the deepest node of the active thread is marked as active, this also returns `true` for every other node within the thread.
+    fn is_on_active_thread(&self, id: &K) -> bool;
     /// Returns `true` if the Weave contains a bookmarked node with the specified identifier.
     fn contains_bookmark(&self, id: &K) -> bool;
+    /// Returns the number of "active" nodes (`node.is_active() == true`) stored within the Weave.
+    ///
+    /// The meaning of this value can depend on the underlying Weave implementation.
+    fn count_active(&self) -> usize;
+    /// Returns the number of bookmarked nodes stored within the Weave.
+    fn count_bookmarked(&self) -> usize;
     /// Returns a reference to the node corresponding to the identifier.
     fn get_node(&self, id: &K) -> Option<&N>;
     /// Builds a list of all node identifiers ordered by their positions in the Weave.
@@ -150,6 +191,48 @@ where
     ///
     /// In Weave implementations where nodes can contain multiple parents, the thread always uses the active parent if one is present, falling back to the first parent if the node does not contain any active parents.
     fn get_thread_from(&mut self, id: &K, output: &mut Vec<K>);
+    /// Returns the thread starting at the deepest active node within the Weave.
+    ///
+    /// This is equivalent to [`Weave::get_active_thread`], but only requires a shared reference to the Weave, allocating any temporary buffers it needs.
+    fn active_thread_vec(&self) -> Vec<K>;
+    /// Returns the thread starting at the specified node.
+    ///
+    /// This is equivalent to [`Weave::get_thread_from`], but only requires a shared reference to the Weave, allocating any temporary buffers it needs.
+    fn thread_from_vec(&self, id: &K) -> Vec<K>;
+    /// Returns the number of nodes separating the specified node from its nearest root node, or `None` if the node could not be found.
+    ///
+    /// Root nodes have a depth of zero. In Weave implementations where nodes can contain multiple parents, the shortest path to a root node is used.
+    fn get_depth(&self, id: &K) -> Option<usize>;
+    /// Returns the number of nodes within the subtree starting at the specified node (including the node itself), or `None` if the node could not be found.
+    ///
+    /// In Weave implementations where nodes can contain multiple parents, each descendant is only counted once.
+    fn subtree_size(&self, id: &K) -> Option<usize>;
+    /// An iterator over the identifiers of every ancestor of the specified node, excluding the node itself.
+    ///
+    /// In Weave implementations where nodes can contain multiple parents, ancestors are visited in breadth-first order and each ancestor is only returned once.
+    fn ancestors(&self, id: &K) -> impl Iterator<Item = K>;
+    /// An iterator over the identifiers of every descendant of the specified node in pre-order, excluding the node itself.
+    ///
+    /// In Weave implementations where nodes can contain multiple parents, each descendant is only returned once.
+    fn descendants(&self, id: &K) -> impl Iterator<Item = K>;
+    /// An iterator over the identifiers of every "leaf" node (nodes which do not have any children), ordered by their positions in the Weave.
+    fn leaves(&self) -> impl Iterator<Item = K>;
+    /// An iterator over every thread which starts at a "leaf" node (a node which does not have any children), ordered by their positions in the Weave.
+    ///
+    /// Threads are built one at a time as the iterator is advanced. In Weave implementations where nodes can contain multiple parents, every distinct path between a root node and a leaf node is returned; the number of such paths can grow exponentially with the number of nodes in densely connected Weaves.
+    fn get_all_threads(&self) -> impl Iterator<Item = Vec<K>>;
+    /// Returns the zero-based position of the specified node among its siblings alongside the total number of siblings (including the node itself), or `None` if the node could not be found.
+    ///
+    /// Siblings are ordered by their positions within the parent node's children, or by their positions within the list of root nodes if the node is a root node. In Weave implementations where nodes can contain multiple parents, the node's first parent is used.
+    fn get_sibling_index(&self, id: &K) -> Option<(usize, usize)>;
+    /// An iterator over the identifiers of every node whose contents match the specified predicate, in no particular order.
+    fn search_contents(&self, predicate: impl Fn(&T) -> bool) -> impl Iterator<Item = K>;
+    /// Returns `true` if the node `ancestor` is an ancestor of the node `descendant`.
+    ///
+    /// A node is not considered to be an ancestor of itself.
+    fn is_ancestor_of(&self, ancestor: &K, descendant: &K) -> bool {
+        self.descendants(ancestor).any(|id| id == *descendant)
+    }
     /// Inserts a node into the Weave.
     ///
     /// Note: This function does not comprehensively check for cyclical connections; doing so must be done by the function caller. Creating a cyclical connection of nodes within a Weave will put the Weave in an invalid state, resulting in unexpected behavior including but not limited to infinite loops and panics.
@@ -180,6 +263,164 @@ where
     fn remove_node_tracked(&mut self, id: &K, on_removal: impl FnMut(N)) -> bool;
     /// Removes all nodes from the Weave.
     fn remove_all_nodes(&mut self);
+    /// Returns up to `take` identifiers of the active thread which come after the node referenced by the `cursor`, along with a cursor for the next page.
+    ///
+    /// Unlike [`Weave::get_active_thread`], identifiers are returned in root-to-tip order. The returned cursor is `None` once the end of the active thread has been reached.
+    ///
+    /// Pages are located using the identifier stored within the `cursor`, allowing the active thread to change between calls. If the cursor's node is no longer part of the active thread, paging restarts from the thread's root.
+    fn active_thread_after(
+        &mut self,
+        cursor: &ThreadCursor<K>,
+        take: usize,
+    ) -> (Vec<K>, Option<ThreadCursor<K>>) {
+        let mut thread = Vec::new();
+        self.get_active_thread(&mut thread);
+        thread.reverse();
+
+        let start = cursor
+            .last
+            .and_then(|last| thread.iter().position(|id| *id == last))
+            .map(|index| index + 1)
+            .unwrap_or(0);
+        let page: Vec<K> = thread[start..].iter().take(take).copied().collect();
+
+        if start + page.len() < thread.len() {
+            let last = page.last().copied().or(cursor.last);
+            (page, Some(ThreadCursor { last }))
+        } else {
+            (page, None)
+        }
+    }
+}
+
+/// A resumable position within a [`Weave`]'s active thread, used by [`Weave::active_thread_after`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ThreadCursor<K> {
+    /// The identifier of the last node which was returned, or `None` if paging should start at the thread's root.
+    pub last: Option<K>,
+}
+
+/// The error returned when a Weave fails to reserve additional capacity.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TryReserveError {
+    /// Reserving capacity within a standard library collection failed.
+    Std(std::collections::TryReserveError),
+    /// Reserving capacity within an [`indexmap`] collection failed.
+    IndexMap(indexmap::TryReserveError),
+}
+
+impl From<std::collections::TryReserveError> for TryReserveError {
+    fn from(value: std::collections::TryReserveError) -> Self {
+        Self::Std(value)
+    }
+}
+
+impl From<indexmap::TryReserveError> for TryReserveError {
+    fn from(value: indexmap::TryReserveError) -> Self {
+        Self::IndexMap(value)
+    }
+}
+
+/// The reason a [`DiscreteWeave`] failed to split a node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitError {
+    /// The node to be split could not be found.
+    NodeNotFound,
+    /// The identifier for the new node is already in use, or is the identifier of the node being split.
+    IdentifierInUse,
+    /// The Weave already contains the maximum number of nodes.
+    CapacityExceeded,
+    /// The node's contents cannot be split at the given index (see [`DiscreteContents::can_split_at`]).
+    InvalidIndex,
+    /// Splitting the node's contents failed (see [`DiscreteContents::split`]).
+    SplitFailed,
+}
+
+/// The reason a Weave could not be constructed from its parts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WeaveError {
+    /// Multiple nodes share an identifier.
+    DuplicateNode,
+    /// A node is connected to itself, or is both a parent and a child of another node.
+    InvalidNode,
+    /// A node is connected to a node which is not present, or a connection is only recorded on one of the two nodes it connects.
+    DanglingEdge,
+    /// The root nodes do not match the nodes without parents.
+    InvalidRoots,
+    /// The active nodes disagree with the nodes' `active` fields, or do not form a single thread starting at a root node.
+    InvalidActive,
+    /// The bookmarked nodes disagree with the nodes' `bookmarked` fields.
+    InvalidBookmarks,
+    /// The Weave would contain more than the maximum number of nodes.
+    CapacityExceeded,
+}
+
+/// A builder for [`Node`] objects, allowing nodes to be constructed without specifying every field.
+///
+/// Nodes created by the builder are inactive, are not bookmarked, and do not have any children unless specified otherwise. The builder can be converted into any node type implementing `From<NodeBuilder>` using [`NodeBuilder::build`].
+#[derive(Debug, Clone)]
+pub struct NodeBuilder<K, T, S>
+where
+    K: Hash + Copy + Eq,
+    S: BuildHasher + Default + Clone,
+{
+    id: K,
+    contents: T,
+    parents: IndexSet<K, S>,
+    active: bool,
+    bookmarked: bool,
+}
+
+impl<K, T, S> NodeBuilder<K, T, S>
+where
+    K: Hash + Copy + Eq,
+    S: BuildHasher + Default + Clone,
+{
+    /// Creates a builder for a node with the specified identifier and contents.
+    pub fn new(id: K, contents: T) -> Self {
+        Self {
+            id,
+            contents,
+            parents: IndexSet::default(),
+            active: false,
+            bookmarked: false,
+        }
+    }
+    /// Adds a parent to the node.
+    ///
+    /// Node types which only support a single parent use the first parent which was added.
+    #[must_use]
+    pub fn parent(mut self, id: K) -> Self {
+        self.parents.insert(id);
+        self
+    }
+    /// Adds multiple parents to the node, in order.
+    ///
+    /// Node types which only support a single parent use the first parent which was added.
+    #[must_use]
+    pub fn parents(mut self, ids: impl IntoIterator<Item = K>) -> Self {
+        self.parents.extend(ids);
+        self
+    }
+    /// Sets if the node should be considered active.
+    #[must_use]
+    pub fn active(mut self, value: bool) -> Self {
+        self.active = value;
+        self
+    }
+    /// Sets if the node is bookmarked.
+    #[must_use]
+    pub fn bookmarked(mut self, value: bool) -> Self {
+        self.bookmarked = value;
+        self
+    }
+    /// Builds the node.
+    pub fn build<N>(self) -> N
+    where
+        N: From<Self>,
+    {
+        N::from(self)
+    }
 }
 
 /// A [`Weave`] containing document-wide metadata.
@@ -216,10 +457,36 @@ where
     fn sort_roots_by(&mut self, cmp: impl FnMut(&N, &N) -> Ordering);
     /// Sorts the identifiers of "root" nodes (nodes which do not have any parents) using the comparison function `cmp`.
     fn sort_roots_by_id(&mut self, cmp: impl FnMut(&K, &K) -> Ordering);
+    /// Moves a child node to the specified position within the children of the parent node, shifting the positions of the other children.
+    ///
+    /// Indexes past the end of the parent node's children are clamped to the last position. Returns `false` if either node does not exist or if `child` is not a child of `parent`.
+    fn move_child_to_index(&mut self, parent: &K, child: &K, index: usize) -> bool;
+    /// Moves a "root" node (a node which does not have any parents) to the specified position within the list of root nodes, shifting the positions of the other root nodes.
+    ///
+    /// Indexes past the end of the list are clamped to the last position. Returns `false` if the node does not exist or is not a root node.
+    fn move_root_to_index(&mut self, id: &K, index: usize) -> bool;
     /// Sorts bookmarked nodes using the comparison function `cmp`.
     fn sort_bookmarks_by(&mut self, cmp: impl FnMut(&N, &N) -> Ordering);
     /// Sorts the identifiers of bookmarked nodes using the comparison function `cmp`.
     fn sort_bookmarks_by_id(&mut self, cmp: impl FnMut(&K, &K) -> Ordering);
+    /// Sorts the child nodes of a parent node with the specified identifier from newest to oldest (see [`TimestampedNode::created_at`]).
+    ///
+    /// Nodes with an unknown creation time are placed after every other node.
+    fn sort_children_by_recency(&mut self, id: &K) -> bool
+    where
+        N: TimestampedNode<K, T>,
+    {
+        self.sort_node_children_by(id, |a, b| b.created_at().cmp(&a.created_at()))
+    }
+    /// Sorts "root" nodes (nodes which do not have any parents) from newest to oldest (see [`TimestampedNode::created_at`]).
+    ///
+    /// Nodes with an unknown creation time are placed after every other node.
+    fn sort_roots_by_recency(&mut self)
+    where
+        N: TimestampedNode<K, T>,
+    {
+        self.sort_roots_by(|a, b| b.created_at().cmp(&a.created_at()));
+    }
 }
 
 /// A [`Weave`] where only one [`Node`] object can be considered "active" at a time.
@@ -269,6 +536,17 @@ where
 {
     /// Mutable access to the contents of a node with the specified identifier.
     fn get_contents_mut<O>(&mut self, id: &K, callback: impl FnOnce(&mut T) -> O) -> Option<O>;
+    /// Replaces the contents of a node with the specified identifier, returning `false` if the node could not be found.
+    ///
+    /// This is equivalent to assigning the contents within [`SemiIndependentWeave::get_contents_mut`].
+    fn set_contents(&mut self, id: &K, contents: T) -> bool {
+        self.get_contents_mut(id, |current| *current = contents)
+            .is_some()
+    }
+    /// Mutable access to a node with the specified identifier, returning `false` if the node could not be found.
+    ///
+    /// The node's connections may be left empty while the callback runs, and changes made to the node's identifier or connections are discarded. Changes made to the node's bookmarked status are applied afterwards. Changes made to the node's active status are applied afterwards as if by [`Weave::set_node_active_status_in_place`], which may change the active status of other nodes if it is necessary to preserve internal consistency.
+    fn with_node_mut(&mut self, id: &K, callback: impl FnOnce(&mut N)) -> bool;
 }
 
 /// A [`Weave`] where the contents of [`Node`] objects can be split and merged.
@@ -280,12 +558,56 @@ where
 {
     /// Splits a node with the specified identifier at the given index, creating a new node with the identifier `new_id`.
     ///
-    /// Returns `false` if splitting the node failed or the node could not be found.
-    fn split_node(&mut self, id: &K, at: usize, new_id: K) -> bool;
+    /// Returns `false` if splitting the node failed, if the node's contents cannot be split at the given index (see [`DiscreteContents::can_split_at`]), or if the node could not be found. Use [`DiscreteWeave::try_split_node`] to determine why splitting failed.
+    fn split_node(&mut self, id: &K, at: usize, new_id: K) -> bool {
+        self.try_split_node(id, at, new_id).is_ok()
+    }
+    /// Splits a node with the specified identifier at the given index, creating a new node with the identifier `new_id`.
+    ///
+    /// Returns the reason splitting failed if the Weave was not modified.
+    fn try_split_node(&mut self, id: &K, at: usize, new_id: K) -> Result<(), SplitError>;
+    /// Splits a node with the specified identifier at each of the given indexes, creating a chain of new nodes using the identifiers in `new_ids`.
+    ///
+    /// Indexes are relative to the node's original contents and must be strictly increasing. The result is equivalent to repeatedly calling [`DiscreteWeave::split_node`] on the last node of the chain, but children of the original node are only relinked once.
+    ///
+    /// Returns `false` without modifying the Weave if the node could not be found, if `ats` and `new_ids` differ in length, if the indexes are not strictly increasing, if any of the new identifiers are duplicated or already exist, or if splitting the node's contents failed.
+    ///
+    /// Each index is checked with [`DiscreteContents::can_split_at`] against the contents remaining after the previous split. Contents which were already split are merged back together if a later index is rejected, which cannot fail for implementations of [`DiscreteContents`] upholding the contracts of [`DiscreteContents::split`] and [`DiscreteContents::merge`]; implementations which break them will cause a panic.
+    fn split_node_multi(&mut self, id: &K, ats: &[usize], new_ids: &[K]) -> bool;
     /// Merges a node with the specified identifier with its parent, with the newly merged node inheriting the parent's identifier.
     ///
     /// Returns the identifier of the merged node if merging was successful.
     fn merge_with_parent(&mut self, id: &K) -> Option<K>;
+    /// Collapses the linear chain of nodes containing the node with the specified identifier into a single node, by repeatedly merging nodes with their parents (see [`DiscreteWeave::merge_with_parent`]).
+    ///
+    /// The node is first merged upwards into its ancestors, and then its descendants are merged downwards into it. Merging stops in each direction at the first node which cannot be merged, such as a node with multiple parents or siblings, or a node whose contents cannot be merged.
+    ///
+    /// Returns the identifier of the final merged node, or `None` if no nodes could be merged.
+    fn merge_chain(&mut self, id: &K) -> Option<K> {
+        let mut current = *id;
+        let mut merged = false;
+
+        while let Some(parent) = self.merge_with_parent(&current) {
+            current = parent;
+            merged = true;
+        }
+
+        loop {
+            let Some(child) = self.descendants(&current).next() else {
+                break;
+            };
+
+            match self.merge_with_parent(&child) {
+                Some(parent) => {
+                    current = parent;
+                    merged = true;
+                }
+                None => break,
+            }
+        }
+
+        merged.then_some(current)
+    }
 }
 
 /// A [`Weave`] where [`Node`] objects can be meaningfully deduplicated by their contents.
@@ -297,6 +619,12 @@ where
 {
     /// An iterator over the specified node's sibling identifiers which contain contents which are duplicates of the specified node's contents.
     fn find_duplicates(&self, id: &K) -> impl Iterator<Item = K>;
+    /// An iterator over groups of node identifiers whose contents are duplicates of each other, searching every node in the Weave rather than only siblings.
+    ///
+    /// Every group contains at least two nodes, and every node within a group is a duplicate of the group's first node. Groups (and the nodes within them) are returned in no particular order.
+    fn find_duplicate_groups(&self) -> impl Iterator<Item = Vec<K>>
+    where
+        T: HashableContents;
 }
 
 #[cfg(feature = "rkyv")]
@@ -365,6 +693,8 @@ where
     fn contains_active(&self, id: &K) -> bool;
     /// Returns `true` if the Weave contains a bookmarked node with the specified identifier.
     fn contains_bookmark(&self, id: &K) -> bool;
+    /// Returns the number of bookmarked nodes stored within the Weave.
+    fn count_bookmarked(&self) -> usize;
     /// Returns a reference to the node corresponding to the identifier.
     fn get_node(&self, id: &K) -> Option<&N>;
     /// Builds a list of all node identifiers ordered by their positions in the Weave.
@@ -383,6 +713,34 @@ where
     ///
     /// In Weave implementations where nodes can contain multiple parents, the thread always uses the active parent if one is present, falling back to the first parent if the node does not contain any active parents.
     fn get_thread_from(&self, id: &K, output: &mut Vec<K>);
+    /// Returns the number of nodes separating the specified node from its nearest root node, or `None` if the node could not be found.
+    ///
+    /// Root nodes have a depth of zero. In Weave implementations where nodes can contain multiple parents, the shortest path to a root node is used.
+    fn get_depth(&self, id: &K) -> Option<usize>;
+    /// Returns the number of nodes within the subtree starting at the specified node (including the node itself), or `None` if the node could not be found.
+    ///
+    /// In Weave implementations where nodes can contain multiple parents, each descendant is only counted once.
+    fn subtree_size(&self, id: &K) -> Option<usize>;
+    /// An iterator over the identifiers of every "leaf" node (nodes which do not have any children), ordered by their positions in the Weave.
+    fn leaves(&self) -> impl Iterator<Item = K>;
+    /// An iterator over the identifiers of every node whose contents match the specified predicate, in no particular order.
+    fn search_contents(&self, predicate: impl Fn(&T) -> bool) -> impl Iterator<Item = K>;
+    /// Builds the contents of the active thread (see [`ArchivedWeave::get_active_thread`]) by concatenating the bytes of each node's contents, starting at the thread's root node.
+    fn get_active_content(&self, output: &mut Vec<u8>)
+    where
+        T: AsRef<[u8]>,
+    {
+        let mut thread = Vec::new();
+        self.get_active_thread(&mut thread);
+
+        output.clear();
+
+        for id in thread.iter().rev() {
+            if let Some(node) = self.get_node(id) {
+                output.extend_from_slice(node.contents().as_ref());
+            }
+        }
+    }
 }
 
 /// An [`ArchivedWeave`] containing document-wide metadata.
@@ -627,3 +985,134 @@ fn descendant_subgraph<'a, K, N, T, S>(
         }
     }
 }
+
+fn node_depth<K, I>(id: K, limit: usize, parents: impl Fn(&K) -> Option<I>) -> Option<usize>
+where
+    K: Hash + Copy + Eq,
+    I: IntoIterator<Item = K>,
+{
+    let mut frontier = vec![id];
+    let mut next_frontier = Vec::new();
+    let mut visited: HashSet<K> = HashSet::from_iter([id]);
+
+    parents(&id)?;
+
+    for depth in 0..=limit {
+        for node in frontier.drain(..) {
+            let Some(node_parents) = parents(&node) else {
+                continue;
+            };
+            let mut node_parents = node_parents.into_iter().peekable();
+
+            if node_parents.peek().is_none() {
+                return Some(depth);
+            }
+
+            next_frontier.extend(node_parents.filter(|parent| visited.insert(*parent)));
+        }
+
+        if next_frontier.is_empty() {
+            break;
+        }
+
+        mem::swap(&mut frontier, &mut next_frontier);
+    }
+
+    None
+}
+
+fn subtree_size<K, I>(id: K, children: impl Fn(&K) -> Option<I>) -> Option<usize>
+where
+    K: Hash + Copy + Eq,
+    I: IntoIterator<Item = K>,
+{
+    let mut stack = vec![id];
+    let mut visited: HashSet<K> = HashSet::from_iter([id]);
+
+    children(&id)?;
+
+    while let Some(node) = stack.pop() {
+        if let Some(node_children) = children(&node) {
+            stack.extend(
+                node_children
+                    .into_iter()
+                    .filter(|child| visited.insert(*child)),
+            );
+        }
+    }
+
+    Some(visited.len())
+}
+
+fn split_contents<T>(contents: T, ats: &[usize]) -> Result<Vec<T>, T>
+where
+    T: DiscreteContents,
+{
+    let mut pieces = Vec::with_capacity(ats.len() + 1);
+    let mut remainder = contents;
+    let mut offset = 0;
+
+    for at in ats {
+        let result = if remainder.can_split_at(at - offset) {
+            remainder.split(at - offset)
+        } else {
+            DiscreteContentResult::One(remainder)
+        };
+
+        match result {
+            DiscreteContentResult::Two(left, right) => {
+                pieces.push(left);
+                remainder = right;
+                offset = *at;
+            }
+            DiscreteContentResult::One(value) => {
+                remainder = value;
+
+                while let Some(piece) = pieces.pop() {
+                    remainder = match piece.merge(remainder) {
+                        DiscreteContentResult::One(value) => value,
+                        DiscreteContentResult::Two(_, _) => {
+                            panic!("contents could not be merged after being split")
+                        }
+                    };
+                }
+
+                return Err(remainder);
+            }
+        }
+    }
+
+    pieces.push(remainder);
+
+    Ok(pieces)
+}
+
+fn duplicate_groups<'a, K, T>(
+    nodes: impl Iterator<Item = (K, &'a T)>,
+) -> impl Iterator<Item = Vec<K>>
+where
+    K: Hash + Copy + Eq,
+    T: HashableContents + 'a,
+{
+    // Contents are hashed with the standard library's hasher rather than the weave's, as weave hashers may be specialized for keys (such as ULID hashers which only support `write_u128`).
+    let hasher = RandomState::new();
+    let mut buckets: HashMap<u64, Vec<(&'a T, Vec<K>)>> = HashMap::new();
+
+    for (id, contents) in nodes {
+        let groups = buckets.entry(hasher.hash_one(contents)).or_default();
+
+        match groups
+            .iter_mut()
+            .find(|(first, _)| first.is_duplicate_of(contents))
+        {
+            Some((_, group)) => group.push(id),
+            None => groups.push((contents, vec![id])),
+        }
+    }
+
+    buckets
+        .into_values()
+        .flatten()
+        .map(|(_, group)| group)
+        .filter(|group| group.len() >= 2)
+}
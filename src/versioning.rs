@@ -4,6 +4,10 @@ use rkyv::{rancor::Fallible, ser::Writer};
 
 /// A set of bytes accompanied by file header information
 ///
+/// The header is 32 bytes long, containing the 24 byte format identifier followed by the format version as a little-endian [`u64`]. Headers written using [`VersionedBytes::write_checked`] are 48 bytes long, with the version followed by a little-endian CRC32 checksum of the data and 12 zeroed padding bytes.
+///
+/// The most significant bit of the stored version is set only in headers written using [`VersionedBytes::write_checked`], allowing checked and unchecked headers to be told apart. Versions must therefore be less than 2<sup>63</sup>.
+///
 /// Note: Buffers deserialized using [`rkyv`] must be [aligned to 16-byte boundaries](https://rkyv.org/format/alignment.html). [`VersionedBytes`] is capable of preserving 16-byte memory alignment if the backing byte buffer is correctly aligned.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct VersionedBytes<'a> {
@@ -21,42 +25,162 @@ impl<'a> VersionedBytes<'a> {
     /// This can fail in the following cases:
     /// - The specified `format_identifier` does not match the first 24 bytes of the byte array
     /// - The byte array is less than 32 bytes long
+    /// - The header was written using [`VersionedBytes::write_checked`]
     pub fn try_from_bytes(value: &'a [u8], format_identifier: [u8; 24]) -> Option<Self> {
         if value.starts_with(&format_identifier) && value.len() >= 32 {
             let (version_bytes, data) = value[24..].split_at(8);
+            let version = u64::from_le_bytes(version_bytes.try_into().unwrap());
+
+            if version & CHECKED_FLAG != 0 {
+                return None;
+            }
 
             Some(Self {
                 format_identifier,
-                version: u64::from_le_bytes(version_bytes.try_into().unwrap()),
+                version,
                 data,
             })
         } else {
             None
         }
     }
+    /// Tries to deserialize a [`VersionedBytes`] struct from a byte array written using [`VersionedBytes::write_checked`], verifying the checksum of the data
+    ///
+    /// This can fail in the following cases:
+    /// - The specified `format_identifier` does not match the first 24 bytes of the byte array, the byte array is less than 48 bytes long, or the header was not written using [`VersionedBytes::write_checked`] ([`ChecksumError::InvalidHeader`])
+    /// - The checksum stored within the header does not match the data ([`ChecksumError::Mismatch`])
+    pub fn try_from_bytes_checked(
+        value: &'a [u8],
+        format_identifier: [u8; 24],
+    ) -> Result<Self, ChecksumError> {
+        if value.starts_with(&format_identifier) && value.len() >= 48 {
+            let (version_bytes, remainder) = value[24..].split_at(8);
+            let (checksum_bytes, remainder) = remainder.split_at(4);
+            let data = &remainder[12..];
+            let version = u64::from_le_bytes(version_bytes.try_into().unwrap());
+
+            if version & CHECKED_FLAG == 0 {
+                return Err(ChecksumError::InvalidHeader);
+            }
+
+            if u32::from_le_bytes(checksum_bytes.try_into().unwrap()) == crc32(data) {
+                Ok(Self {
+                    format_identifier,
+                    version: version & !CHECKED_FLAG,
+                    data,
+                })
+            } else {
+                Err(ChecksumError::Mismatch)
+            }
+        } else {
+            Err(ChecksumError::InvalidHeader)
+        }
+    }
     /// The total length in bytes after serialization
     pub fn output_length(&self) -> usize {
         32 + self.data.len()
     }
+    /// The total length in bytes after serialization using [`VersionedBytes::write_checked`]
+    pub fn checked_output_length(&self) -> usize {
+        48 + self.data.len()
+    }
+    /// Calculates the CRC32 checksum of the data
+    pub fn checksum(&self) -> u32 {
+        crc32(self.data)
+    }
     /// Serializes the header into the specified writer
+    ///
+    /// The version must be less than 2<sup>63</sup>; in debug builds, this panics otherwise, as the resulting header would be rejected by [`VersionedBytes::try_from_bytes`].
     pub fn write_header<W: Writer + Fallible>(
         &self,
         writer: &mut W,
     ) -> Result<(), <W as Fallible>::Error> {
+        debug_assert!(self.version & CHECKED_FLAG == 0);
+
         writer.write(&self.format_identifier)?;
         writer.write(&self.version.to_le_bytes())?;
 
         Ok(())
     }
     /// Serializes the header and contents into the specified writer
+    ///
+    /// See [`VersionedBytes::write_header`] for restrictions on the version.
     pub fn write<W: Writer + Fallible>(
         &self,
         writer: &mut W,
     ) -> Result<(), <W as Fallible>::Error> {
+        self.write_header(writer)?;
+        writer.write(self.data)?;
+
+        Ok(())
+    }
+    /// Serializes the header (including the checksum of the data) into the specified writer
+    ///
+    /// The version must be less than 2<sup>63</sup>; in debug builds, this panics otherwise, as the most significant bit of the stored version is used to mark checked headers.
+    pub fn write_header_checked<W: Writer + Fallible>(
+        &self,
+        writer: &mut W,
+    ) -> Result<(), <W as Fallible>::Error> {
+        debug_assert!(self.version & CHECKED_FLAG == 0);
+
         writer.write(&self.format_identifier)?;
-        writer.write(&self.version.to_le_bytes())?;
+        writer.write(&(self.version | CHECKED_FLAG).to_le_bytes())?;
+        writer.write(&self.checksum().to_le_bytes())?;
+        writer.write(&[0; 12])?;
+
+        Ok(())
+    }
+    /// Serializes the header (including the checksum of the data) and contents into the specified writer
+    ///
+    /// See [`VersionedBytes::write_header_checked`] for restrictions on the version.
+    pub fn write_checked<W: Writer + Fallible>(
+        &self,
+        writer: &mut W,
+    ) -> Result<(), <W as Fallible>::Error> {
+        self.write_header_checked(writer)?;
         writer.write(self.data)?;
 
         Ok(())
     }
 }
+
+/// The reason a [`VersionedBytes`] struct could not be deserialized using [`VersionedBytes::try_from_bytes_checked`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChecksumError {
+    /// The byte array does not start with the expected format identifier, is too short to contain a header, or contains a header which was not written using [`VersionedBytes::write_checked`]
+    InvalidHeader,
+    /// The byte array contains a valid header, but the data does not match the header's checksum
+    Mismatch,
+}
+
+const CHECKED_FLAG: u64 = 1 << 63;
+
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut index = 0;
+
+    while index < 256 {
+        let mut value = index as u32;
+        let mut bit = 0;
+
+        while bit < 8 {
+            value = if value & 1 == 1 {
+                (value >> 1) ^ 0xEDB88320
+            } else {
+                value >> 1
+            };
+            bit += 1;
+        }
+
+        table[index] = value;
+        index += 1;
+    }
+
+    table
+};
+
+fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(u32::MAX, |crc, byte| {
+        CRC32_TABLE[((crc ^ *byte as u32) & 0xFF) as usize] ^ (crc >> 8)
+    })
+}
@@ -3,14 +3,16 @@
 use std::{
     cmp::Ordering,
     collections::{HashMap, VecDeque},
+    fmt,
     hash::{BuildHasher, Hash},
     marker::PhantomData,
 };
 
 use crate::{
     ActivePathWeave, ActiveSingularWeave, DeduplicatableContents, DeduplicatableWeave,
-    DiscreteContents, DiscreteWeave, IndependentContents, IndependentWeave, MetadataWeave, Node,
-    SemiIndependentWeave, SortableWeave, Weave, dependent, independent,
+    DiscreteContents, DiscreteWeave, HashableContents, IndependentContents, IndependentWeave,
+    MetadataWeave, Node, SemiIndependentWeave, SortableWeave, SplitError, Weave, dependent,
+    independent,
 };
 
 #[cfg(feature = "rkyv")]
@@ -247,6 +249,8 @@ pub struct WeaveActionCount {
     pub move_node: usize,
     /// [`SemiIndependentWeave::get_contents_mut()`]
     pub get_contents_mut: usize,
+    /// [`SemiIndependentWeave::with_node_mut()`]
+    pub with_node_mut: usize,
     /// [`DiscreteWeave::split_node()`]
     pub split_node: usize,
     /// [`DiscreteWeave::merge_with_parent()`]
@@ -277,6 +281,7 @@ impl WeaveActionCount {
             .saturating_add(self.sort_bookmarks)
             .saturating_add(self.move_node)
             .saturating_add(self.get_contents_mut)
+            .saturating_add(self.with_node_mut)
             .saturating_add(self.split_node)
             .saturating_add(self.merge_with_parent)
             .saturating_add(self.other)
@@ -651,9 +656,18 @@ where
     fn contains_active(&self, id: &K) -> bool {
         self.weave.contains_active(id)
     }
+    fn is_on_active_thread(&self, id: &K) -> bool {
+        self.weave.is_on_active_thread(id)
+    }
     fn contains_bookmark(&self, id: &K) -> bool {
         self.weave.contains_bookmark(id)
     }
+    fn count_active(&self) -> usize {
+        self.weave.count_active()
+    }
+    fn count_bookmarked(&self) -> usize {
+        self.weave.count_bookmarked()
+    }
     fn get_node(&self, id: &K) -> Option<&N> {
         self.weave.get_node(id)
     }
@@ -669,6 +683,36 @@ where
     fn get_thread_from(&mut self, id: &K, output: &mut Vec<K>) {
         self.weave.get_thread_from(id, output);
     }
+    fn active_thread_vec(&self) -> Vec<K> {
+        self.weave.active_thread_vec()
+    }
+    fn thread_from_vec(&self, id: &K) -> Vec<K> {
+        self.weave.thread_from_vec(id)
+    }
+    fn get_depth(&self, id: &K) -> Option<usize> {
+        self.weave.get_depth(id)
+    }
+    fn subtree_size(&self, id: &K) -> Option<usize> {
+        self.weave.subtree_size(id)
+    }
+    fn ancestors(&self, id: &K) -> impl Iterator<Item = K> {
+        self.weave.ancestors(id)
+    }
+    fn descendants(&self, id: &K) -> impl Iterator<Item = K> {
+        self.weave.descendants(id)
+    }
+    fn leaves(&self) -> impl Iterator<Item = K> {
+        self.weave.leaves()
+    }
+    fn get_all_threads(&self) -> impl Iterator<Item = Vec<K>> {
+        self.weave.get_all_threads()
+    }
+    fn search_contents(&self, predicate: impl Fn(&T) -> bool) -> impl Iterator<Item = K> {
+        self.weave.search_contents(predicate)
+    }
+    fn get_sibling_index(&self, id: &K) -> Option<(usize, usize)> {
+        self.weave.get_sibling_index(id)
+    }
     fn add_node(&mut self, node: N) -> bool {
         if self.weave.add_node(node.clone()) {
             self.actions.push_back(WeaveAction::AddNode(node));
@@ -818,6 +862,35 @@ where
             children: self.weave.roots().into_iter().copied().collect(),
         });
     }
+    fn move_child_to_index(&mut self, parent: &K, child: &K, index: usize) -> bool {
+        if self.weave.move_child_to_index(parent, child, index) {
+            self.actions.push_back(WeaveAction::SetNodeChildOrdering {
+                parent: Some(*parent),
+                children: self
+                    .weave
+                    .get_node(parent)
+                    .unwrap()
+                    .to()
+                    .into_iter()
+                    .copied()
+                    .collect(),
+            });
+            true
+        } else {
+            false
+        }
+    }
+    fn move_root_to_index(&mut self, id: &K, index: usize) -> bool {
+        if self.weave.move_root_to_index(id, index) {
+            self.actions.push_back(WeaveAction::SetNodeChildOrdering {
+                parent: None,
+                children: self.weave.roots().into_iter().copied().collect(),
+            });
+            true
+        } else {
+            false
+        }
+    }
     fn sort_bookmarks_by(&mut self, cmp: impl FnMut(&N, &N) -> Ordering) {
         self.weave.sort_bookmarks_by(cmp);
         self.actions.push_back(WeaveAction::SetBookmarkOrdering(
@@ -861,7 +934,7 @@ where
     W: IndependentWeave<K, N, T>,
     K: Hash + Copy + Eq,
     N: Node<K, T> + Clone,
-    T: IndependentContents + Clone,
+    T: IndependentContents + Clone + PartialEq,
 {
     fn move_node(&mut self, id: &K, new_parents: &[K]) -> bool {
         if self.weave.move_node(id, new_parents) {
@@ -881,7 +954,7 @@ where
     W: SemiIndependentWeave<K, N, T>,
     K: Hash + Copy + Eq,
     N: Node<K, T> + Clone,
-    T: IndependentContents + Clone,
+    T: IndependentContents + Clone + PartialEq,
 {
     fn get_contents_mut<O>(&mut self, id: &K, callback: impl FnOnce(&mut T) -> O) -> Option<O> {
         self.weave.get_contents_mut(id, |contents| {
@@ -895,6 +968,44 @@ where
             output
         })
     }
+    fn with_node_mut(&mut self, id: &K, callback: impl FnOnce(&mut N)) -> bool {
+        let active = self.weave.contains_active(id);
+        let bookmarked = self.weave.contains_bookmark(id);
+        let mut changed_contents = None;
+
+        let found = self.weave.with_node_mut(id, |node| {
+            let contents = node.contents().clone();
+
+            callback(node);
+
+            if *node.contents() != contents {
+                changed_contents = Some(node.contents().clone());
+            }
+        });
+
+        if found {
+            if let Some(contents) = changed_contents {
+                self.actions
+                    .push_back(WeaveAction::SetNodeContent { id: *id, contents });
+            }
+            if self.weave.contains_bookmark(id) != bookmarked {
+                self.actions
+                    .push_back(WeaveAction::SetNodeBookmarkedStatus {
+                        id: *id,
+                        value: !bookmarked,
+                    });
+            }
+            if self.weave.contains_active(id) != active {
+                self.actions
+                    .push_back(WeaveAction::SetNodeActiveStatusInPlace {
+                        id: *id,
+                        value: !active,
+                    });
+            }
+        }
+
+        found
+    }
 }
 
 impl<W, K, N, T, M> DiscreteWeave<K, N, T> for LoggedWeave<W, K, N, T, M>
@@ -904,13 +1015,28 @@ where
     N: Node<K, T> + Clone,
     T: DiscreteContents,
 {
-    fn split_node(&mut self, id: &K, at: usize, new_id: K) -> bool {
-        if self.weave.split_node(id, at, new_id) {
-            self.actions.push_back(WeaveAction::SplitNode {
-                id: *id,
-                at,
-                new_id,
-            });
+    fn try_split_node(&mut self, id: &K, at: usize, new_id: K) -> Result<(), SplitError> {
+        self.weave.try_split_node(id, at, new_id)?;
+        self.actions.push_back(WeaveAction::SplitNode {
+            id: *id,
+            at,
+            new_id,
+        });
+        Ok(())
+    }
+    fn split_node_multi(&mut self, id: &K, ats: &[usize], new_ids: &[K]) -> bool {
+        if self.weave.split_node_multi(id, ats, new_ids) {
+            let mut previous = (*id, 0);
+
+            for (at, new_id) in ats.iter().zip(new_ids) {
+                self.actions.push_back(WeaveAction::SplitNode {
+                    id: previous.0,
+                    at: at - previous.1,
+                    new_id: *new_id,
+                });
+                previous = (*new_id, *at);
+            }
+
             true
         } else {
             false
@@ -938,6 +1064,12 @@ where
     fn find_duplicates(&self, id: &K) -> impl Iterator<Item = K> {
         self.weave.find_duplicates(id)
     }
+    fn find_duplicate_groups(&self) -> impl Iterator<Item = Vec<K>>
+    where
+        T: HashableContents,
+    {
+        self.weave.find_duplicate_groups()
+    }
 }
 
 impl<W, K, N, T> Weave<K, N, T> for CountedWeave<W, K, N, T>
@@ -971,9 +1103,18 @@ where
     fn contains_active(&self, id: &K) -> bool {
         self.weave.contains_active(id)
     }
+    fn is_on_active_thread(&self, id: &K) -> bool {
+        self.weave.is_on_active_thread(id)
+    }
     fn contains_bookmark(&self, id: &K) -> bool {
         self.weave.contains_bookmark(id)
     }
+    fn count_active(&self) -> usize {
+        self.weave.count_active()
+    }
+    fn count_bookmarked(&self) -> usize {
+        self.weave.count_bookmarked()
+    }
     fn get_node(&self, id: &K) -> Option<&N> {
         self.weave.get_node(id)
     }
@@ -989,6 +1130,36 @@ where
     fn get_thread_from(&mut self, id: &K, output: &mut Vec<K>) {
         self.weave.get_thread_from(id, output);
     }
+    fn active_thread_vec(&self) -> Vec<K> {
+        self.weave.active_thread_vec()
+    }
+    fn thread_from_vec(&self, id: &K) -> Vec<K> {
+        self.weave.thread_from_vec(id)
+    }
+    fn get_depth(&self, id: &K) -> Option<usize> {
+        self.weave.get_depth(id)
+    }
+    fn subtree_size(&self, id: &K) -> Option<usize> {
+        self.weave.subtree_size(id)
+    }
+    fn ancestors(&self, id: &K) -> impl Iterator<Item = K> {
+        self.weave.ancestors(id)
+    }
+    fn descendants(&self, id: &K) -> impl Iterator<Item = K> {
+        self.weave.descendants(id)
+    }
+    fn leaves(&self) -> impl Iterator<Item = K> {
+        self.weave.leaves()
+    }
+    fn get_all_threads(&self) -> impl Iterator<Item = Vec<K>> {
+        self.weave.get_all_threads()
+    }
+    fn search_contents(&self, predicate: impl Fn(&T) -> bool) -> impl Iterator<Item = K> {
+        self.weave.search_contents(predicate)
+    }
+    fn get_sibling_index(&self, id: &K) -> Option<(usize, usize)> {
+        self.weave.get_sibling_index(id)
+    }
     fn add_node(&mut self, node: N) -> bool {
         if self.weave.add_node(node) {
             self.count.add_node = self.count.add_node.saturating_add(1);
@@ -1100,6 +1271,22 @@ where
         self.count.sort_roots = self.count.sort_roots.saturating_add(1);
         self.weave.sort_roots_by_id(cmp);
     }
+    fn move_child_to_index(&mut self, parent: &K, child: &K, index: usize) -> bool {
+        if self.weave.move_child_to_index(parent, child, index) {
+            self.count.sort_node_children = self.count.sort_node_children.saturating_add(1);
+            true
+        } else {
+            false
+        }
+    }
+    fn move_root_to_index(&mut self, id: &K, index: usize) -> bool {
+        if self.weave.move_root_to_index(id, index) {
+            self.count.sort_roots = self.count.sort_roots.saturating_add(1);
+            true
+        } else {
+            false
+        }
+    }
     fn sort_bookmarks_by(&mut self, cmp: impl FnMut(&N, &N) -> Ordering) {
         self.count.sort_bookmarks = self.count.sort_bookmarks.saturating_add(1);
         self.weave.sort_bookmarks_by(cmp);
@@ -1164,6 +1351,14 @@ where
             callback(contents)
         })
     }
+    fn with_node_mut(&mut self, id: &K, callback: impl FnOnce(&mut N)) -> bool {
+        if self.weave.with_node_mut(id, callback) {
+            self.count.with_node_mut = self.count.with_node_mut.saturating_add(1);
+            true
+        } else {
+            false
+        }
+    }
 }
 
 impl<W, K, N, T> DiscreteWeave<K, N, T> for CountedWeave<W, K, N, T>
@@ -1173,9 +1368,14 @@ where
     N: Node<K, T>,
     T: DiscreteContents,
 {
-    fn split_node(&mut self, id: &K, at: usize, new_id: K) -> bool {
-        if self.weave.split_node(id, at, new_id) {
-            self.count.split_node = self.count.split_node.saturating_add(1);
+    fn try_split_node(&mut self, id: &K, at: usize, new_id: K) -> Result<(), SplitError> {
+        self.weave.try_split_node(id, at, new_id)?;
+        self.count.split_node = self.count.split_node.saturating_add(1);
+        Ok(())
+    }
+    fn split_node_multi(&mut self, id: &K, ats: &[usize], new_ids: &[K]) -> bool {
+        if self.weave.split_node_multi(id, ats, new_ids) {
+            self.count.split_node = self.count.split_node.saturating_add(new_ids.len());
             true
         } else {
             false
@@ -1202,4 +1402,468 @@ where
     fn find_duplicates(&self, id: &K) -> impl Iterator<Item = K> {
         self.weave.find_duplicates(id)
     }
+    fn find_duplicate_groups(&self) -> impl Iterator<Item = Vec<K>>
+    where
+        T: HashableContents,
+    {
+        self.weave.find_duplicate_groups()
+    }
+}
+
+/// A [`Weave`] wrapper which notifies an observer of changes successfully made to the inner [`Weave`].
+///
+/// Events are emitted after the corresponding change has been applied. See [`WeaveEvent`] for the complete list of events.
+///
+/// Unlike [`LoggedWeave`], this wrapper does not implement traits whose changes cannot be described by a [`WeaveEvent`] (such as [`DiscreteWeave`] and [`MetadataWeave`]).
+pub struct ObservedWeave<W, K, N, T>
+where
+    W: Weave<K, N, T>,
+    K: Hash + Copy + Eq,
+    N: Node<K, T>,
+{
+    /// The [`Weave`] being wrapped.
+    ///
+    /// Changes made directly to the inner [`Weave`] (without using the wrapper's functions) are not observed.
+    pub weave: W,
+
+    observer: Option<Box<dyn FnMut(WeaveEvent<K>)>>,
+
+    _phantom_n: PhantomData<N>,
+    _phantom_t: PhantomData<T>,
+}
+
+/// A change made to a [`Weave`], as reported by [`ObservedWeave`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WeaveEvent<K> {
+    /// A node was added to the [`Weave`].
+    NodeAdded(K),
+    /// A node was removed from the [`Weave`].
+    ///
+    /// This is emitted separately for each node removed by a single removal.
+    NodeRemoved(K),
+    /// The set of active nodes may have changed.
+    ActiveChanged,
+    /// The bookmarked status of a node changed.
+    BookmarkChanged(K),
+    /// The children of a node were reordered.
+    ChildrenReordered(K),
+    /// The root nodes were reordered.
+    RootsReordered,
+    /// The bookmarks were reordered.
+    BookmarksReordered,
+    /// A node was moved to a new set of parents.
+    NodeMoved(K),
+    /// The contents of a node were mutably accessed.
+    ContentsChanged(K),
+}
+
+impl<W, K, N, T> fmt::Debug for ObservedWeave<W, K, N, T>
+where
+    W: Weave<K, N, T> + fmt::Debug,
+    K: Hash + Copy + Eq,
+    N: Node<K, T>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ObservedWeave")
+            .field("weave", &self.weave)
+            .field("observer", &self.observer.is_some())
+            .finish()
+    }
+}
+
+impl<W, K, N, T> AsRef<W> for ObservedWeave<W, K, N, T>
+where
+    W: Weave<K, N, T>,
+    K: Hash + Copy + Eq,
+    N: Node<K, T>,
+{
+    fn as_ref(&self) -> &W {
+        &self.weave
+    }
+}
+
+impl<W, K, N, T> From<W> for ObservedWeave<W, K, N, T>
+where
+    W: Weave<K, N, T>,
+    K: Hash + Copy + Eq,
+    N: Node<K, T>,
+{
+    fn from(value: W) -> Self {
+        Self {
+            weave: value,
+            observer: None,
+            _phantom_n: PhantomData,
+            _phantom_t: PhantomData,
+        }
+    }
+}
+
+impl<W, K, N, T> ObservedWeave<W, K, N, T>
+where
+    W: Weave<K, N, T>,
+    K: Hash + Copy + Eq,
+    N: Node<K, T>,
+{
+    pub fn into_weave(self) -> W {
+        self.weave
+    }
+    /// Sets the function which will be called for each [`WeaveEvent`], replacing any existing observer.
+    pub fn set_observer(&mut self, f: Box<dyn FnMut(WeaveEvent<K>)>) {
+        self.observer = Some(f);
+    }
+    /// Removes the current observer (if one is set), returning it.
+    pub fn take_observer(&mut self) -> Option<Box<dyn FnMut(WeaveEvent<K>)>> {
+        self.observer.take()
+    }
+    fn emit(&mut self, event: WeaveEvent<K>) {
+        if let Some(observer) = &mut self.observer {
+            observer(event);
+        }
+    }
+}
+
+impl<W, K, N, T> Weave<K, N, T> for ObservedWeave<W, K, N, T>
+where
+    W: Weave<K, N, T>,
+    K: Hash + Copy + Eq,
+    N: Node<K, T>,
+{
+    type Nodes = W::Nodes;
+    type Roots = W::Roots;
+    type Bookmarks = W::Bookmarks;
+
+    fn len(&self) -> usize {
+        self.weave.len()
+    }
+    fn is_empty(&self) -> bool {
+        self.weave.is_empty()
+    }
+    fn nodes(&self) -> &Self::Nodes {
+        self.weave.nodes()
+    }
+    fn roots(&self) -> &Self::Roots {
+        self.weave.roots()
+    }
+    fn bookmarks(&self) -> &Self::Bookmarks {
+        self.weave.bookmarks()
+    }
+    fn contains(&self, id: &K) -> bool {
+        self.weave.contains(id)
+    }
+    fn contains_active(&self, id: &K) -> bool {
+        self.weave.contains_active(id)
+    }
+    fn is_on_active_thread(&self, id: &K) -> bool {
+        self.weave.is_on_active_thread(id)
+    }
+    fn contains_bookmark(&self, id: &K) -> bool {
+        self.weave.contains_bookmark(id)
+    }
+    fn count_active(&self) -> usize {
+        self.weave.count_active()
+    }
+    fn count_bookmarked(&self) -> usize {
+        self.weave.count_bookmarked()
+    }
+    fn get_node(&self, id: &K) -> Option<&N> {
+        self.weave.get_node(id)
+    }
+    fn get_ordered_node_identifiers(&mut self, output: &mut Vec<K>) {
+        self.weave.get_ordered_node_identifiers(output);
+    }
+    fn get_ordered_node_identifiers_from(&mut self, id: &K, output: &mut Vec<K>) {
+        self.weave.get_ordered_node_identifiers_from(id, output);
+    }
+    fn get_active_thread(&mut self, output: &mut Vec<K>) {
+        self.weave.get_active_thread(output);
+    }
+    fn get_thread_from(&mut self, id: &K, output: &mut Vec<K>) {
+        self.weave.get_thread_from(id, output);
+    }
+    fn active_thread_vec(&self) -> Vec<K> {
+        self.weave.active_thread_vec()
+    }
+    fn thread_from_vec(&self, id: &K) -> Vec<K> {
+        self.weave.thread_from_vec(id)
+    }
+    fn get_depth(&self, id: &K) -> Option<usize> {
+        self.weave.get_depth(id)
+    }
+    fn subtree_size(&self, id: &K) -> Option<usize> {
+        self.weave.subtree_size(id)
+    }
+    fn ancestors(&self, id: &K) -> impl Iterator<Item = K> {
+        self.weave.ancestors(id)
+    }
+    fn descendants(&self, id: &K) -> impl Iterator<Item = K> {
+        self.weave.descendants(id)
+    }
+    fn leaves(&self) -> impl Iterator<Item = K> {
+        self.weave.leaves()
+    }
+    fn get_all_threads(&self) -> impl Iterator<Item = Vec<K>> {
+        self.weave.get_all_threads()
+    }
+    fn search_contents(&self, predicate: impl Fn(&T) -> bool) -> impl Iterator<Item = K> {
+        self.weave.search_contents(predicate)
+    }
+    fn get_sibling_index(&self, id: &K) -> Option<(usize, usize)> {
+        self.weave.get_sibling_index(id)
+    }
+    fn add_node(&mut self, node: N) -> bool {
+        let id = node.id();
+
+        if self.weave.add_node(node) {
+            self.emit(WeaveEvent::NodeAdded(id));
+            if self.weave.contains_active(&id) {
+                self.emit(WeaveEvent::ActiveChanged);
+            }
+            if self.weave.contains_bookmark(&id) {
+                self.emit(WeaveEvent::BookmarkChanged(id));
+            }
+            true
+        } else {
+            false
+        }
+    }
+    fn set_node_active_status(&mut self, id: &K, value: bool, alternate: bool) -> bool {
+        if self.weave.set_node_active_status(id, value, alternate) {
+            self.emit(WeaveEvent::ActiveChanged);
+            true
+        } else {
+            false
+        }
+    }
+    fn set_node_active_status_in_place(&mut self, id: &K, value: bool) -> bool {
+        if self.weave.set_node_active_status_in_place(id, value) {
+            self.emit(WeaveEvent::ActiveChanged);
+            true
+        } else {
+            false
+        }
+    }
+    fn set_node_bookmarked_status(&mut self, id: &K, value: bool) -> bool {
+        if self.weave.set_node_bookmarked_status(id, value) {
+            self.emit(WeaveEvent::BookmarkChanged(*id));
+            true
+        } else {
+            false
+        }
+    }
+    fn remove_node(&mut self, id: &K) -> Option<N> {
+        let mut output = None;
+
+        self.remove_node_tracked(id, |node| {
+            if node.id() == *id {
+                output = Some(node);
+            }
+        });
+
+        output
+    }
+    fn remove_node_tracked(&mut self, id: &K, mut on_removal: impl FnMut(N)) -> bool {
+        let mut removed = Vec::new();
+        let active_thread = self.weave.active_thread_vec();
+
+        if self.weave.remove_node_tracked(id, |node| {
+            removed.push(node.id());
+            on_removal(node);
+        }) {
+            for id in removed {
+                self.emit(WeaveEvent::NodeRemoved(id));
+            }
+            if self.weave.active_thread_vec() != active_thread {
+                self.emit(WeaveEvent::ActiveChanged);
+            }
+            true
+        } else {
+            false
+        }
+    }
+    fn remove_all_nodes(&mut self) {
+        let mut removed = Vec::new();
+        let active = self.weave.count_active() > 0;
+
+        self.weave.get_ordered_node_identifiers(&mut removed);
+        self.weave.remove_all_nodes();
+
+        for id in removed {
+            self.emit(WeaveEvent::NodeRemoved(id));
+        }
+        if active {
+            self.emit(WeaveEvent::ActiveChanged);
+        }
+    }
+}
+
+impl<W, K, N, T> SortableWeave<K, N, T> for ObservedWeave<W, K, N, T>
+where
+    W: SortableWeave<K, N, T>,
+    K: Hash + Copy + Eq,
+    N: Node<K, T>,
+{
+    fn get_ordered_node_identifiers_reversed_children(&mut self, output: &mut Vec<K>) {
+        self.weave
+            .get_ordered_node_identifiers_reversed_children(output);
+    }
+    fn get_ordered_node_identifiers_from_reversed_children(&mut self, id: &K, output: &mut Vec<K>) {
+        self.weave
+            .get_ordered_node_identifiers_from_reversed_children(id, output);
+    }
+    fn sort_node_children_by(&mut self, id: &K, cmp: impl FnMut(&N, &N) -> Ordering) -> bool {
+        if self.weave.sort_node_children_by(id, cmp) {
+            self.emit(WeaveEvent::ChildrenReordered(*id));
+            true
+        } else {
+            false
+        }
+    }
+    fn sort_node_children_by_id(&mut self, id: &K, cmp: impl FnMut(&K, &K) -> Ordering) -> bool {
+        if self.weave.sort_node_children_by_id(id, cmp) {
+            self.emit(WeaveEvent::ChildrenReordered(*id));
+            true
+        } else {
+            false
+        }
+    }
+    fn sort_roots_by(&mut self, cmp: impl FnMut(&N, &N) -> Ordering) {
+        self.weave.sort_roots_by(cmp);
+        self.emit(WeaveEvent::RootsReordered);
+    }
+    fn sort_roots_by_id(&mut self, cmp: impl FnMut(&K, &K) -> Ordering) {
+        self.weave.sort_roots_by_id(cmp);
+        self.emit(WeaveEvent::RootsReordered);
+    }
+    fn move_child_to_index(&mut self, parent: &K, child: &K, index: usize) -> bool {
+        if self.weave.move_child_to_index(parent, child, index) {
+            self.emit(WeaveEvent::ChildrenReordered(*parent));
+            true
+        } else {
+            false
+        }
+    }
+    fn move_root_to_index(&mut self, id: &K, index: usize) -> bool {
+        if self.weave.move_root_to_index(id, index) {
+            self.emit(WeaveEvent::RootsReordered);
+            true
+        } else {
+            false
+        }
+    }
+    fn sort_bookmarks_by(&mut self, cmp: impl FnMut(&N, &N) -> Ordering) {
+        self.weave.sort_bookmarks_by(cmp);
+        self.emit(WeaveEvent::BookmarksReordered);
+    }
+    fn sort_bookmarks_by_id(&mut self, cmp: impl FnMut(&K, &K) -> Ordering) {
+        self.weave.sort_bookmarks_by_id(cmp);
+        self.emit(WeaveEvent::BookmarksReordered);
+    }
+}
+
+impl<W, K, N, T> ActiveSingularWeave<K, N, T> for ObservedWeave<W, K, N, T>
+where
+    W: ActiveSingularWeave<K, N, T>,
+    K: Hash + Copy + Eq,
+    N: Node<K, T>,
+{
+    fn active(&self) -> Option<K> {
+        self.weave.active()
+    }
+}
+
+impl<W, K, N, T> ActivePathWeave<K, N, T> for ObservedWeave<W, K, N, T>
+where
+    W: ActivePathWeave<K, N, T>,
+    K: Hash + Copy + Eq,
+    N: Node<K, T>,
+{
+    type Active = W::Active;
+
+    fn active(&self) -> &Self::Active {
+        self.weave.active()
+    }
+}
+
+impl<W, K, N, T> IndependentWeave<K, N, T> for ObservedWeave<W, K, N, T>
+where
+    W: IndependentWeave<K, N, T>,
+    K: Hash + Copy + Eq,
+    N: Node<K, T>,
+    T: IndependentContents + Clone + PartialEq,
+{
+    fn move_node(&mut self, id: &K, new_parents: &[K]) -> bool {
+        let active_thread = self.weave.active_thread_vec();
+
+        if self.weave.move_node(id, new_parents) {
+            self.emit(WeaveEvent::NodeMoved(*id));
+            if self.weave.active_thread_vec() != active_thread {
+                self.emit(WeaveEvent::ActiveChanged);
+            }
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl<W, K, N, T> SemiIndependentWeave<K, N, T> for ObservedWeave<W, K, N, T>
+where
+    W: SemiIndependentWeave<K, N, T>,
+    K: Hash + Copy + Eq,
+    N: Node<K, T>,
+    T: IndependentContents + Clone + PartialEq,
+{
+    fn get_contents_mut<O>(&mut self, id: &K, callback: impl FnOnce(&mut T) -> O) -> Option<O> {
+        let output = self.weave.get_contents_mut(id, callback);
+
+        if output.is_some() {
+            self.emit(WeaveEvent::ContentsChanged(*id));
+        }
+
+        output
+    }
+    fn with_node_mut(&mut self, id: &K, callback: impl FnOnce(&mut N)) -> bool {
+        let bookmarked = self.weave.contains_bookmark(id);
+        let active_thread = self.weave.active_thread_vec();
+        let mut changed_contents = false;
+
+        if self.weave.with_node_mut(id, |node| {
+            let contents = node.contents().clone();
+
+            callback(node);
+
+            changed_contents = *node.contents() != contents;
+        }) {
+            if changed_contents {
+                self.emit(WeaveEvent::ContentsChanged(*id));
+            }
+            if self.weave.contains_bookmark(id) != bookmarked {
+                self.emit(WeaveEvent::BookmarkChanged(*id));
+            }
+            if self.weave.active_thread_vec() != active_thread {
+                self.emit(WeaveEvent::ActiveChanged);
+            }
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl<W, K, N, T> DeduplicatableWeave<K, N, T> for ObservedWeave<W, K, N, T>
+where
+    W: DeduplicatableWeave<K, N, T>,
+    K: Hash + Copy + Eq,
+    N: Node<K, T>,
+    T: DeduplicatableContents,
+{
+    fn find_duplicates(&self, id: &K) -> impl Iterator<Item = K> {
+        self.weave.find_duplicates(id)
+    }
+    fn find_duplicate_groups(&self) -> impl Iterator<Item = Vec<K>>
+    where
+        T: HashableContents,
+    {
+        self.weave.find_duplicate_groups()
+    }
 }
@@ -5,8 +5,9 @@ use proptest::{prelude::*, strategy::Strategy, test_runner::Config};
 use proptest_derive::Arbitrary;
 use proptest_state_machine::{ReferenceStateMachine, StateMachineTest, prop_state_machine};
 use universal_weave::{
-    DiscreteContentResult, DiscreteContents, DiscreteWeave, IndependentContents, MetadataWeave,
-    SemiIndependentWeave, SortableWeave, Weave,
+    ActiveSingularWeave, DeduplicatableContents, DiscreteContentResult, DiscreteContents,
+    DiscreteWeave, IndependentContents, MetadataWeave, NodeBuilder, SemiIndependentWeave,
+    SortableWeave, SplitError, ThreadCursor, Weave,
     dependent::{DependentNode, DependentWeave},
 };
 
@@ -86,6 +87,10 @@ enum WeaveTransition {
         value: bool,
         id_seed: u32,
     },
+    BookmarkMatching {
+        value: bool,
+        content_seed: u32,
+    },
     #[proptest(weight = 3)]
     RemoveNode {
         id_seed: u32,
@@ -97,6 +102,21 @@ enum WeaveTransition {
     RemoveAllNodes {
         apply_seed: u16,
     },
+    Clear {
+        apply_seed: u16,
+    },
+    ClearActive {
+        apply_seed: u8,
+    },
+    ActivateToLeaf {
+        id_seed: u32,
+    },
+    PruneToActive {
+        apply_seed: u8,
+    },
+    Retain {
+        content_seed: u32,
+    },
     MetadataMut {
         content_seed: u32,
     },
@@ -120,19 +140,44 @@ enum WeaveTransition {
     SortBookmarksById {
         sort_seed: u32,
     },
+    MoveChildToIndex {
+        id_seed: u32,
+        index: u8,
+    },
+    MoveRootToIndex {
+        id_seed: u32,
+        index: u8,
+    },
+    MoveNode {
+        new_parent_seed: Option<u32>,
+        id_seed: u32,
+    },
     GetContentsMut {
         id_seed: u32,
         content_seed: u32,
     },
+    WithNodeMut {
+        id_seed: u32,
+        content_seed: u32,
+        active: bool,
+        bookmarked: bool,
+    },
     #[proptest(weight = 3)]
     SplitNode {
         at_seed: u32,
         id_seed: u32,
     },
+    SplitNodeMulti {
+        at_seeds: (u32, u32),
+        id_seed: u32,
+    },
     #[proptest(weight = 3)]
     MergeNodeWithParent {
         id_seed: u32,
     },
+    MergeChain {
+        id_seed: u32,
+    },
 }
 
 struct WeaveWrapper {
@@ -142,6 +187,10 @@ struct WeaveWrapper {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Deserialize, rkyv::Serialize)
+)]
 struct WeaveContent {
     length: u32,
     content_seed: u32,
@@ -232,11 +281,25 @@ impl StateMachineTest for WeaveWrapper {
                 }
             }
             WeaveTransition::GetActiveThread => {
-                state.weave.get_active_thread(&mut state.scratchpad)
+                state.weave.get_active_thread(&mut state.scratchpad);
+                assert_eq!(state.weave.active_thread_vec(), state.scratchpad);
+
+                for id in state.weave.nodes().keys() {
+                    assert_eq!(
+                        state.weave.is_on_active_thread(id),
+                        state.scratchpad.contains(id)
+                    );
+                }
+            }
+            WeaveTransition::GetThreadFrom { id_seed } => {
+                state
+                    .weave
+                    .get_thread_from(&map_id(id_seed), &mut state.scratchpad);
+                assert_eq!(
+                    state.weave.thread_from_vec(&map_id(id_seed)),
+                    state.scratchpad
+                );
             }
-            WeaveTransition::GetThreadFrom { id_seed } => state
-                .weave
-                .get_thread_from(&map_id(id_seed), &mut state.scratchpad),
             WeaveTransition::AddNode {
                 from_seed,
                 active,
@@ -275,6 +338,20 @@ impl StateMachineTest for WeaveWrapper {
                     .weave
                     .set_node_bookmarked_status(&map_id(id_seed), value);
             }
+            WeaveTransition::BookmarkMatching {
+                value,
+                content_seed,
+            } => {
+                if value {
+                    state
+                        .weave
+                        .bookmark_matching(|n| n.contents.content_seed == content_seed % 4);
+                } else {
+                    state
+                        .weave
+                        .unbookmark_matching(|n| n.contents.content_seed == content_seed % 4);
+                }
+            }
             WeaveTransition::RemoveNode { id_seed } => {
                 state.weave.remove_node(&map_id(id_seed));
             }
@@ -286,6 +363,70 @@ impl StateMachineTest for WeaveWrapper {
                     state.weave.remove_all_nodes();
                 }
             }
+            WeaveTransition::MoveNode {
+                id_seed,
+                new_parent_seed,
+            } => {
+                let id = map_id(id_seed);
+                let new_parent = new_parent_seed.map(&map_id);
+                let cyclic = new_parent
+                    .is_some_and(|parent| parent == id || state.weave.is_ancestor_of(&id, &parent));
+
+                let moved = state.weave.move_node(&id, new_parent);
+                assert_eq!(
+                    moved,
+                    state.weave.contains(&id)
+                        && new_parent.is_none_or(|parent| state.weave.contains(&parent))
+                        && !cyclic
+                );
+            }
+            WeaveTransition::Retain { content_seed } => {
+                state
+                    .weave
+                    .retain(|n| n.contents.content_seed != content_seed % 4);
+            }
+            WeaveTransition::Clear { apply_seed } => {
+                if apply_seed == 0 {
+                    let metadata = *state.weave.metadata();
+                    state.weave.clear();
+                    assert!(state.weave.is_empty());
+                    assert_eq!(*state.weave.metadata(), metadata);
+                }
+            }
+            WeaveTransition::ActivateToLeaf { id_seed } => {
+                let id = map_id(id_seed);
+                let mut leaf = state.weave.get_node(&id).map(|node| node.id);
+                while let Some(child) = leaf
+                    .and_then(|leaf| state.weave.get_node(&leaf))
+                    .and_then(|node| node.to.first())
+                {
+                    leaf = Some(*child);
+                }
+
+                assert_eq!(state.weave.activate_to_leaf(&id), leaf.is_some());
+
+                if let Some(leaf) = leaf {
+                    let thread = state.weave.active_thread_vec();
+                    assert_eq!(thread.first(), Some(&leaf));
+                    assert!(thread.contains(&id));
+                }
+            }
+            WeaveTransition::PruneToActive { apply_seed } => {
+                if apply_seed % 16 == 0 {
+                    let thread = state.weave.active_thread_vec();
+                    state.weave.prune_to_active();
+                    assert_eq!(state.weave.active_thread_vec(), thread);
+                    assert_eq!(state.weave.len(), thread.len());
+                }
+            }
+            WeaveTransition::ClearActive { apply_seed } => {
+                if apply_seed % 8 == 0 {
+                    let len = state.weave.len();
+                    state.weave.clear_active();
+                    assert_eq!(state.weave.count_active(), 0);
+                    assert_eq!(state.weave.len(), len);
+                }
+            }
             WeaveTransition::MetadataMut { content_seed } => {
                 state.weave.metadata_mut(|m| *m = content_seed)
             }
@@ -309,6 +450,40 @@ impl StateMachineTest for WeaveWrapper {
                     hash_value(a.id as u64 + sort_seed).cmp(&hash_value(b.id as u64 + sort_seed))
                 });
             }
+            WeaveTransition::MoveChildToIndex { id_seed, index } => {
+                let child = map_id(id_seed);
+                let parent = state.weave.get_node(&child).and_then(|node| node.from);
+
+                if let Some(parent) = parent {
+                    assert!(
+                        state
+                            .weave
+                            .move_child_to_index(&parent, &child, index as usize)
+                    );
+                    let siblings = &state.weave.get_node(&parent).unwrap().to;
+                    assert_eq!(
+                        siblings.get_index_of(&child),
+                        Some((index as usize).min(siblings.len() - 1))
+                    );
+                } else {
+                    assert!(
+                        !state
+                            .weave
+                            .move_child_to_index(&child, &child, index as usize)
+                    );
+                }
+            }
+            WeaveTransition::MoveRootToIndex { id_seed, index } => {
+                let id = map_id(id_seed);
+                let moved = state.weave.move_root_to_index(&id, index as usize);
+                assert_eq!(moved, state.weave.roots().contains(&id));
+                if moved {
+                    assert_eq!(
+                        state.weave.roots().get_index_of(&id),
+                        Some((index as usize).min(state.weave.roots().len() - 1))
+                    );
+                }
+            }
             WeaveTransition::SortRootsById { sort_seed } => {
                 let sort_seed = sort_seed as u64;
                 state.weave.sort_roots_by_id(|a, b| {
@@ -335,6 +510,33 @@ impl StateMachineTest for WeaveWrapper {
                     .weave
                     .get_contents_mut(&map_id(id_seed), |c| c.content_seed = content_seed % 4);
             }
+            WeaveTransition::WithNodeMut {
+                id_seed,
+                content_seed,
+                active,
+                bookmarked,
+            } => {
+                let id = map_id(id_seed);
+                let old_node = state.weave.get_node(&id).cloned();
+
+                let found = state.weave.with_node_mut(&id, |node| {
+                    node.from = None;
+                    node.to.clear();
+                    node.active = active;
+                    node.bookmarked = bookmarked;
+                    node.contents.content_seed = content_seed % 4;
+                });
+                assert_eq!(found, old_node.is_some());
+
+                if let Some(old_node) = old_node {
+                    let node = state.weave.get_node(&id).unwrap();
+                    assert_eq!(node.from, old_node.from);
+                    assert_eq!(node.to, old_node.to);
+                    assert_eq!(node.active, active);
+                    assert_eq!(state.weave.contains_bookmark(&id), bookmarked);
+                    assert_eq!(node.contents.content_seed, content_seed % 4);
+                }
+            }
             WeaveTransition::SplitNode { id_seed, at_seed } => {
                 state.weave.split_node(
                     &map_id(id_seed),
@@ -350,9 +552,43 @@ impl StateMachineTest for WeaveWrapper {
                     state.counter,
                 );
             }
+            WeaveTransition::SplitNodeMulti { id_seed, at_seeds } => {
+                let id = map_id(id_seed);
+                let ats: Vec<usize> = state
+                    .weave
+                    .get_node(&id)
+                    .map(|node| {
+                        [at_seeds.0, at_seeds.1]
+                            .into_iter()
+                            .map(|seed| {
+                                seed.checked_rem(node.contents.length).unwrap_or_default() as usize
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                let new_ids = [state.counter, state.counter + 1];
+                let split = state.weave.split_node_multi(&id, &ats, &new_ids);
+
+                if split {
+                    assert!(ats.is_sorted_by(|a, b| a < b));
+                    assert!(
+                        state
+                            .weave
+                            .get_node(&id)
+                            .unwrap()
+                            .to
+                            .iter()
+                            .eq([&new_ids[0]])
+                    );
+                    state.counter += 1;
+                }
+            }
             WeaveTransition::MergeNodeWithParent { id_seed } => {
                 state.weave.merge_with_parent(&map_id(id_seed));
             }
+            WeaveTransition::MergeChain { id_seed } => {
+                state.weave.merge_chain(&map_id(id_seed));
+            }
         }
         if state.weave.nodes().len() > old_node_count {
             state.counter += 1;
@@ -360,10 +596,60 @@ impl StateMachineTest for WeaveWrapper {
         state
     }
     fn check_invariants(
-        _state: &Self::SystemUnderTest,
+        state: &Self::SystemUnderTest,
         _ref_state: &<Self::Reference as ReferenceStateMachine>::State,
     ) {
+        let nodes = state.weave.nodes().values();
+
+        assert_eq!(
+            state.weave.count_active(),
+            nodes.clone().filter(|node| node.active).count()
+        );
+        assert_eq!(
+            state.weave.count_bookmarked(),
+            nodes.filter(|node| node.bookmarked).count()
+        );
+    }
+}
+
+#[test]
+fn active_thread_pagination() {
+    let mut weave: DependentWeave<u32, WeaveContent, u32, RandomState> =
+        DependentWeave::with_capacity(10, 0);
+
+    for id in 0..10 {
+        assert!(weave.add_node(DependentNode {
+            id,
+            from: id.checked_sub(1),
+            to: IndexSet::default(),
+            active: id == 9,
+            bookmarked: false,
+            contents: WeaveContent {
+                length: 1,
+                content_seed: 0,
+            },
+        }));
     }
+
+    let (page, cursor) = weave.active_thread_after(&ThreadCursor::default(), 4);
+    assert_eq!(page, vec![0, 1, 2, 3]);
+    let cursor = cursor.unwrap();
+    assert_eq!(cursor.last, Some(3));
+
+    let (page, cursor) = weave.active_thread_after(&cursor, 4);
+    assert_eq!(page, vec![4, 5, 6, 7]);
+    let cursor = cursor.unwrap();
+
+    let (page, end) = weave.active_thread_after(&cursor, 4);
+    assert_eq!(page, vec![8, 9]);
+    assert_eq!(end, None);
+
+    assert!(weave.remove_node(&7).is_some());
+    assert!(weave.set_node_active_status(&6, true, false));
+
+    let (page, cursor) = weave.active_thread_after(&cursor, 4);
+    assert_eq!(page, vec![0, 1, 2, 3]);
+    assert_eq!(cursor, Some(ThreadCursor { last: Some(3) }));
 }
 
 /*
@@ -381,3 +667,1044 @@ fn transition_set() {
     }
 }
 */
+
+#[test]
+fn node_depth() {
+    let mut weave: DependentWeave<u32, WeaveContent, u32, RandomState> =
+        DependentWeave::with_capacity(7, 0);
+
+    for (id, from) in [
+        (0, None),
+        (1, Some(0)),
+        (2, Some(1)),
+        (3, Some(2)),
+        (4, Some(3)),
+        (5, None),
+        (6, Some(2)),
+    ] {
+        assert!(weave.add_node(DependentNode {
+            id,
+            from,
+            to: IndexSet::default(),
+            active: false,
+            bookmarked: false,
+            contents: WeaveContent {
+                length: 1,
+                content_seed: 0,
+            },
+        }));
+    }
+
+    assert_eq!(weave.get_depth(&0), Some(0));
+    assert_eq!(weave.get_depth(&4), Some(4));
+    assert_eq!(weave.get_depth(&5), Some(0));
+    assert_eq!(weave.get_depth(&6), Some(3));
+    assert_eq!(weave.get_depth(&7), None);
+}
+
+#[test]
+fn node_ancestors() {
+    let mut weave: DependentWeave<u32, WeaveContent, u32, RandomState> =
+        DependentWeave::with_capacity(4, 0);
+
+    for (id, from) in [(0, None), (1, Some(0)), (2, Some(1)), (3, Some(1))] {
+        assert!(weave.add_node(DependentNode {
+            id,
+            from,
+            to: IndexSet::default(),
+            active: false,
+            bookmarked: false,
+            contents: WeaveContent {
+                length: 1,
+                content_seed: 0,
+            },
+        }));
+    }
+
+    assert_eq!(weave.ancestors(&2).collect::<Vec<_>>(), vec![1, 0]);
+    assert_eq!(weave.ancestors(&3).collect::<Vec<_>>(), vec![1, 0]);
+    assert_eq!(weave.ancestors(&0).count(), 0);
+    assert_eq!(weave.ancestors(&4).count(), 0);
+}
+
+#[test]
+fn node_descendants() {
+    let mut weave: DependentWeave<u32, WeaveContent, u32, RandomState> =
+        DependentWeave::with_capacity(5, 0);
+
+    for (id, from) in [
+        (0, None),
+        (1, Some(0)),
+        (2, Some(1)),
+        (3, Some(1)),
+        (4, Some(2)),
+    ] {
+        assert!(weave.add_node(DependentNode {
+            id,
+            from,
+            to: IndexSet::default(),
+            active: false,
+            bookmarked: false,
+            contents: WeaveContent {
+                length: 1,
+                content_seed: 0,
+            },
+        }));
+    }
+
+    assert_eq!(weave.descendants(&0).collect::<Vec<_>>(), vec![1, 2, 4, 3]);
+    assert_eq!(weave.descendants(&3).count(), 0);
+    assert_eq!(weave.descendants(&5).count(), 0);
+}
+
+#[test]
+fn leaf_nodes() {
+    let mut weave: DependentWeave<u32, WeaveContent, u32, RandomState> =
+        DependentWeave::with_capacity(6, 0);
+
+    for (id, from) in [
+        (0, None),
+        (1, Some(0)),
+        (2, Some(1)),
+        (3, Some(1)),
+        (4, Some(2)),
+        (5, None),
+    ] {
+        assert!(weave.add_node(DependentNode {
+            id,
+            from,
+            to: IndexSet::default(),
+            active: false,
+            bookmarked: false,
+            contents: WeaveContent {
+                length: 1,
+                content_seed: 0,
+            },
+        }));
+    }
+
+    assert_eq!(weave.leaves().collect::<Vec<_>>(), vec![4, 3, 5]);
+}
+
+#[test]
+fn batch_insertion() {
+    let mut weave: DependentWeave<u32, WeaveContent, u32, RandomState> =
+        DependentWeave::with_capacity(4, 0);
+
+    let node = |id, from| DependentNode {
+        id,
+        from,
+        to: IndexSet::default(),
+        active: id == 2,
+        bookmarked: false,
+        contents: WeaveContent {
+            length: 1,
+            content_seed: 0,
+        },
+    };
+
+    assert_eq!(
+        weave.add_nodes([
+            node(2, Some(1)),
+            node(1, Some(0)),
+            node(0, None),
+            node(0, None),
+            node(3, Some(4)),
+        ]),
+        vec![true, true, true, false, false]
+    );
+    assert_eq!(weave.len(), 3);
+    assert!(weave.contains_active(&2));
+    assert_eq!(weave.ancestors(&2).collect::<Vec<_>>(), vec![1, 0]);
+}
+
+#[test]
+fn lowest_common_ancestor() {
+    let mut weave: DependentWeave<u32, WeaveContent, u32, RandomState> =
+        DependentWeave::with_capacity(6, 0);
+
+    for (id, from) in [
+        (0, None),
+        (1, Some(0)),
+        (2, Some(1)),
+        (3, Some(1)),
+        (4, Some(2)),
+        (5, None),
+    ] {
+        assert!(weave.add_node(DependentNode {
+            id,
+            from,
+            to: IndexSet::default(),
+            active: false,
+            bookmarked: false,
+            contents: WeaveContent {
+                length: 1,
+                content_seed: 0,
+            },
+        }));
+    }
+
+    assert_eq!(weave.lowest_common_ancestor(&4, &3), Some(1));
+    assert_eq!(weave.lowest_common_ancestor(&4, &2), Some(2));
+    assert_eq!(weave.lowest_common_ancestor(&4, &4), Some(4));
+    assert_eq!(weave.lowest_common_ancestor(&4, &5), None);
+    assert_eq!(weave.lowest_common_ancestor(&4, &6), None);
+}
+
+#[test]
+fn node_ancestry() {
+    let mut weave: DependentWeave<u32, WeaveContent, u32, RandomState> =
+        DependentWeave::with_capacity(4, 0);
+
+    for (id, from) in [(0, None), (1, Some(0)), (2, Some(1)), (3, Some(0))] {
+        assert!(weave.add_node(DependentNode {
+            id,
+            from,
+            to: IndexSet::default(),
+            active: false,
+            bookmarked: false,
+            contents: WeaveContent {
+                length: 1,
+                content_seed: 0,
+            },
+        }));
+    }
+
+    assert!(weave.is_ancestor_of(&0, &2));
+    assert!(weave.is_ancestor_of(&1, &2));
+    assert!(!weave.is_ancestor_of(&2, &1));
+    assert!(!weave.is_ancestor_of(&3, &2));
+    assert!(!weave.is_ancestor_of(&2, &2));
+    assert!(!weave.is_ancestor_of(&4, &2));
+}
+
+#[test]
+fn zero_capacity() {
+    let mut weave: DependentWeave<u32, WeaveContent, u32, RandomState> =
+        DependentWeave::with_capacity(0, 0);
+    assert_eq!(weave.capacity(), 0);
+
+    for id in 0..64 {
+        assert!(weave.add_node(DependentNode {
+            id,
+            from: id.checked_sub(1),
+            to: IndexSet::default(),
+            active: id == 63,
+            bookmarked: false,
+            contents: WeaveContent {
+                length: 1,
+                content_seed: 0,
+            },
+        }));
+    }
+
+    assert_eq!(weave.len(), 64);
+    assert!(weave.capacity() >= 64);
+
+    weave.reserve(64);
+    assert!(weave.capacity() >= 128);
+
+    assert_eq!(weave.try_reserve(128), Ok(()));
+    assert!(weave.capacity() >= 192);
+    assert_eq!(weave.len(), 64);
+}
+
+#[test]
+fn retain_nodes() {
+    let mut weave: DependentWeave<u32, WeaveContent, u32, RandomState> =
+        DependentWeave::with_capacity(5, 0);
+
+    for (id, from) in [
+        (0, None),
+        (1, Some(0)),
+        (2, Some(1)),
+        (3, Some(2)),
+        (4, Some(0)),
+    ] {
+        assert!(weave.add_node(DependentNode {
+            id,
+            from,
+            to: IndexSet::default(),
+            active: id == 3,
+            bookmarked: false,
+            contents: WeaveContent {
+                length: 1,
+                content_seed: id % 2,
+            },
+        }));
+    }
+
+    weave.retain(|n| n.id != 2 && n.id != 3);
+    assert_eq!(weave.len(), 3);
+    assert!(weave.contains_active(&1));
+
+    weave.retain(|n| n.contents.content_seed == 0);
+    assert_eq!(weave.len(), 2);
+    assert!(weave.contains_active(&0));
+}
+
+fn deep_chain(depth: u32) -> DependentWeave<u32, WeaveContent, (), RandomState> {
+    let nodes = (0..depth)
+        .map(|id| {
+            (
+                id,
+                WeaveContent {
+                    length: 1,
+                    content_seed: id,
+                },
+            )
+        })
+        .collect();
+    let parents: Vec<i32> = (-1..depth as i32 - 1).collect();
+
+    DependentWeave::from_parent_pointer(nodes, &parents, ()).unwrap()
+}
+
+#[test]
+fn remove_deep_chain() {
+    const DEPTH: u32 = 100_000;
+
+    let mut weave = deep_chain(DEPTH);
+    assert!(weave.set_node_active_status_in_place(&(DEPTH - 1), true));
+    assert!(weave.set_node_bookmarked_status(&(DEPTH - 1), true));
+
+    assert!(weave.remove_node(&0).is_some());
+    assert!(weave.is_empty());
+    assert!(weave.roots().is_empty());
+    assert!(weave.bookmarks().is_empty());
+    assert!(!weave.contains_active(&(DEPTH - 1)));
+}
+
+#[test]
+fn deep_active_thread() {
+    const DEPTH: u32 = 200_000;
+
+    let mut weave = deep_chain(DEPTH);
+    assert!(weave.set_node_active_status_in_place(&(DEPTH - 1), true));
+
+    let mut thread = Vec::new();
+    weave.get_active_thread(&mut thread);
+    assert!(thread.iter().copied().eq((0..DEPTH).rev()));
+
+    thread.clear();
+    weave.get_thread_from(&(DEPTH / 2), &mut thread);
+    assert!(thread.iter().copied().eq((0..=DEPTH / 2).rev()));
+}
+
+#[test]
+fn parent_pointer() {
+    let mut weave: DependentWeave<u32, WeaveContent, u32, RandomState> =
+        DependentWeave::with_capacity(6, 3);
+
+    for (id, from) in [
+        (0, None),
+        (1, Some(0)),
+        (2, Some(0)),
+        (3, Some(2)),
+        (4, None),
+        (5, Some(4)),
+    ] {
+        assert!(weave.add_node(DependentNode {
+            id,
+            from,
+            to: IndexSet::default(),
+            active: false,
+            bookmarked: false,
+            contents: WeaveContent {
+                length: 1,
+                content_seed: id,
+            },
+        }));
+    }
+
+    let (nodes, parents) = weave.to_parent_pointer();
+    assert_eq!(nodes.len(), 6);
+    assert_eq!(parents[0], -1);
+
+    for (node, parent) in nodes.iter().zip(&parents) {
+        let expected = weave.get_node(&node.0).unwrap().from;
+        assert_eq!(
+            expected,
+            usize::try_from(*parent).ok().map(|index| nodes[index].0)
+        );
+    }
+
+    let rebuilt = DependentWeave::<u32, WeaveContent, u32, RandomState>::from_parent_pointer(
+        nodes.clone(),
+        &parents,
+        3,
+    )
+    .unwrap();
+    assert_eq!(rebuilt.nodes(), weave.nodes());
+    assert_eq!(rebuilt.roots(), weave.roots());
+    assert_eq!(*rebuilt.metadata(), 3);
+
+    let mut invalid = parents.clone();
+    invalid[1] = 1;
+    assert!(
+        DependentWeave::<u32, WeaveContent, u32, RandomState>::from_parent_pointer(
+            nodes.clone(),
+            &invalid,
+            3
+        )
+        .is_none()
+    );
+    assert!(
+        DependentWeave::<u32, WeaveContent, u32, RandomState>::from_parent_pointer(
+            nodes,
+            &parents[1..],
+            3
+        )
+        .is_none()
+    );
+}
+
+#[test]
+fn move_node() {
+    let mut weave: DependentWeave<u32, WeaveContent, (), RandomState> =
+        DependentWeave::with_capacity(5, ());
+
+    for (id, from) in [
+        (0, None),
+        (1, Some(0)),
+        (2, Some(1)),
+        (3, Some(0)),
+        (4, None),
+    ] {
+        assert!(weave.add_node(DependentNode {
+            id,
+            from,
+            to: IndexSet::default(),
+            active: id == 2,
+            bookmarked: false,
+            contents: WeaveContent {
+                length: 1,
+                content_seed: id,
+            },
+        }));
+    }
+
+    assert!(!weave.move_node(&1, Some(1)));
+    assert!(!weave.move_node(&1, Some(2)));
+    assert!(!weave.move_node(&0, Some(2)));
+    assert!(!weave.move_node(&1, Some(5)));
+    assert!(!weave.move_node(&5, None));
+
+    assert!(weave.move_node(&1, Some(3)));
+    assert_eq!(weave.get_node(&1).unwrap().from, Some(3));
+    assert!(!weave.get_node(&0).unwrap().to.contains(&1));
+    assert!(weave.get_node(&3).unwrap().to.contains(&1));
+    assert_eq!(weave.get_node(&2).unwrap().from, Some(1));
+    assert!(weave.contains_active(&2));
+
+    let mut thread = Vec::new();
+    weave.get_active_thread(&mut thread);
+    assert_eq!(thread, vec![2, 1, 3, 0]);
+
+    assert!(weave.move_node(&3, None));
+    assert!(weave.roots().contains(&3));
+    assert!(weave.move_node(&3, Some(4)));
+    assert!(!weave.roots().contains(&3));
+    assert_eq!(weave.get_node(&4).unwrap().to.len(), 1);
+    assert!(weave.move_node(&3, Some(4)));
+}
+
+#[test]
+fn sibling_navigation() {
+    let mut weave: DependentWeave<u32, WeaveContent, (), RandomState> =
+        DependentWeave::with_capacity(6, ());
+
+    for (id, from) in [
+        (0, None),
+        (1, Some(0)),
+        (2, Some(0)),
+        (3, Some(0)),
+        (4, None),
+        (5, None),
+    ] {
+        assert!(weave.add_node(DependentNode {
+            id,
+            from,
+            to: IndexSet::default(),
+            active: false,
+            bookmarked: false,
+            contents: WeaveContent {
+                length: 1,
+                content_seed: id,
+            },
+        }));
+    }
+
+    assert_eq!(weave.next_sibling(&1), Some(2));
+    assert_eq!(weave.next_sibling(&2), Some(3));
+    assert_eq!(weave.next_sibling(&3), None);
+    assert_eq!(weave.prev_sibling(&3), Some(2));
+    assert_eq!(weave.prev_sibling(&1), None);
+
+    assert_eq!(weave.next_sibling(&0), Some(4));
+    assert_eq!(weave.prev_sibling(&5), Some(4));
+    assert_eq!(weave.next_sibling(&5), None);
+
+    assert_eq!(weave.next_sibling(&6), None);
+    assert_eq!(weave.prev_sibling(&6), None);
+}
+
+#[test]
+fn sibling_index() {
+    let mut weave: DependentWeave<u32, WeaveContent, (), RandomState> =
+        DependentWeave::with_capacity(5, ());
+
+    for (id, from) in [
+        (0, None),
+        (1, Some(0)),
+        (2, Some(0)),
+        (3, Some(0)),
+        (4, None),
+    ] {
+        assert!(weave.add_node(DependentNode {
+            id,
+            from,
+            to: IndexSet::default(),
+            active: false,
+            bookmarked: false,
+            contents: WeaveContent {
+                length: 1,
+                content_seed: id,
+            },
+        }));
+    }
+
+    assert_eq!(weave.get_sibling_index(&1), Some((0, 3)));
+    assert_eq!(weave.get_sibling_index(&3), Some((2, 3)));
+    assert_eq!(weave.get_sibling_index(&0), Some((0, 2)));
+    assert_eq!(weave.get_sibling_index(&4), Some((1, 2)));
+    assert_eq!(weave.get_sibling_index(&5), None);
+}
+
+#[test]
+fn move_to_index() {
+    let mut weave: DependentWeave<u32, WeaveContent, (), RandomState> =
+        DependentWeave::with_capacity(6, ());
+
+    for (id, from) in [
+        (0, None),
+        (1, Some(0)),
+        (2, Some(0)),
+        (3, Some(0)),
+        (4, None),
+        (5, None),
+    ] {
+        assert!(weave.add_node(DependentNode {
+            id,
+            from,
+            to: IndexSet::default(),
+            active: false,
+            bookmarked: false,
+            contents: WeaveContent {
+                length: 1,
+                content_seed: id,
+            },
+        }));
+    }
+
+    assert!(weave.move_child_to_index(&0, &3, 0));
+    assert!(weave.get_node(&0).unwrap().to.iter().copied().eq([3, 1, 2]));
+    assert!(weave.move_child_to_index(&0, &3, 10));
+    assert!(weave.get_node(&0).unwrap().to.iter().copied().eq([1, 2, 3]));
+    assert!(!weave.move_child_to_index(&0, &4, 0));
+    assert!(!weave.move_child_to_index(&6, &1, 0));
+
+    assert!(weave.move_root_to_index(&5, 0));
+    assert!(weave.roots().iter().copied().eq([5, 0, 4]));
+    assert!(weave.move_root_to_index(&5, usize::MAX));
+    assert!(weave.roots().iter().copied().eq([0, 4, 5]));
+    assert!(!weave.move_root_to_index(&1, 0));
+}
+
+#[test]
+fn all_threads() {
+    let mut weave: DependentWeave<u32, WeaveContent, (), RandomState> =
+        DependentWeave::with_capacity(5, ());
+
+    for (id, from) in [
+        (0, None),
+        (1, Some(0)),
+        (2, Some(1)),
+        (3, Some(0)),
+        (4, None),
+    ] {
+        assert!(weave.add_node(DependentNode {
+            id,
+            from,
+            to: IndexSet::default(),
+            active: false,
+            bookmarked: false,
+            contents: WeaveContent {
+                length: 1,
+                content_seed: id,
+            },
+        }));
+    }
+
+    assert_eq!(
+        weave.get_all_threads().collect::<Vec<_>>(),
+        vec![vec![2, 1, 0], vec![3, 0], vec![4]]
+    );
+
+    for thread in weave.get_all_threads().collect::<Vec<_>>() {
+        let mut expected = Vec::new();
+        weave.get_thread_from(&thread[0], &mut expected);
+        assert_eq!(thread, expected);
+    }
+}
+
+#[test]
+fn subtree_size() {
+    let mut weave: DependentWeave<u32, WeaveContent, (), RandomState> =
+        DependentWeave::with_capacity(5, ());
+
+    for (id, from) in [
+        (0, None),
+        (1, Some(0)),
+        (2, Some(1)),
+        (3, Some(0)),
+        (4, None),
+    ] {
+        assert!(weave.add_node(DependentNode {
+            id,
+            from,
+            to: IndexSet::default(),
+            active: false,
+            bookmarked: false,
+            contents: WeaveContent {
+                length: 1,
+                content_seed: id,
+            },
+        }));
+    }
+
+    assert_eq!(weave.subtree_size(&0), Some(4));
+    assert_eq!(weave.subtree_size(&1), Some(2));
+    assert_eq!(weave.subtree_size(&3), Some(1));
+    assert_eq!(weave.subtree_size(&4), Some(1));
+    assert_eq!(weave.subtree_size(&5), None);
+}
+
+#[test]
+fn node_builder() {
+    let mut weave: DependentWeave<u32, WeaveContent, (), RandomState> =
+        DependentWeave::with_capacity(3, ());
+
+    let contents = |id| WeaveContent {
+        length: 1,
+        content_seed: id,
+    };
+
+    assert!(weave.add_node(NodeBuilder::new(0, contents(0)).build()));
+    assert!(weave.add_node(NodeBuilder::new(1, contents(1)).parent(0).build()));
+    assert!(
+        weave.add_node(
+            NodeBuilder::new(2, contents(2))
+                .parents([1, 0])
+                .active(true)
+                .bookmarked(true)
+                .build()
+        )
+    );
+
+    assert_eq!(
+        weave.get_node(&2),
+        Some(&DependentNode {
+            id: 2,
+            from: Some(1),
+            to: IndexSet::default(),
+            active: true,
+            bookmarked: true,
+            contents: contents(2),
+        })
+    );
+    assert_eq!(weave.active(), Some(2));
+}
+
+#[test]
+fn split_node_multi() {
+    let mut weave: DependentWeave<u32, WeaveContent, (), RandomState> =
+        DependentWeave::with_capacity(6, ());
+
+    for (id, length, from) in [(0, 10, None), (1, 1, Some(0)), (2, 1, Some(0))] {
+        assert!(weave.add_node(DependentNode {
+            id,
+            from,
+            to: IndexSet::default(),
+            active: false,
+            bookmarked: id == 0,
+            contents: WeaveContent {
+                length,
+                content_seed: 0,
+            },
+        }));
+    }
+
+    assert!(!weave.split_node_multi(&0, &[2, 5], &[3]));
+    assert!(!weave.split_node_multi(&0, &[5, 2], &[3, 4]));
+    assert!(!weave.split_node_multi(&0, &[2, 5], &[3, 3]));
+    assert!(!weave.split_node_multi(&0, &[2, 5], &[3, 1]));
+    assert!(!weave.split_node_multi(&0, &[2, 10], &[3, 4]));
+    assert_eq!(weave.len(), 3);
+    assert_eq!(weave.get_node(&0).unwrap().contents.length, 10);
+
+    assert!(weave.split_node_multi(&0, &[2, 5], &[3, 4]));
+    assert_eq!(weave.len(), 5);
+
+    let chain: Vec<_> = [0, 3, 4]
+        .iter()
+        .map(|id| {
+            let node = weave.get_node(id).unwrap();
+            (
+                node.contents.length,
+                node.from,
+                node.to.iter().copied().collect::<Vec<_>>(),
+                node.bookmarked,
+            )
+        })
+        .collect();
+    assert_eq!(
+        chain,
+        vec![
+            (2, None, vec![3], true),
+            (3, Some(0), vec![4], false),
+            (5, Some(3), vec![1, 2], false),
+        ]
+    );
+    assert_eq!(weave.get_node(&1).unwrap().from, Some(4));
+    assert_eq!(weave.get_node(&2).unwrap().from, Some(4));
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct TextContent(String);
+
+impl DiscreteContents for TextContent {
+    fn split(mut self, at: usize) -> DiscreteContentResult<Self> {
+        if at == 0 || at >= self.0.len() {
+            DiscreteContentResult::One(self)
+        } else {
+            let right = self.0.split_off(at);
+            DiscreteContentResult::Two(self, TextContent(right))
+        }
+    }
+    fn can_split_at(&self, at: usize) -> bool {
+        self.0.is_char_boundary(at)
+    }
+    fn merge(mut self, value: Self) -> DiscreteContentResult<Self> {
+        self.0.push_str(&value.0);
+        DiscreteContentResult::One(self)
+    }
+}
+
+impl DeduplicatableContents for TextContent {
+    fn is_duplicate_of(&self, other: &Self) -> bool {
+        self == other
+    }
+}
+
+#[test]
+fn split_at_char_boundary() {
+    let mut weave: DependentWeave<u32, TextContent, (), RandomState> =
+        DependentWeave::with_capacity(4, ());
+
+    assert!(weave.add_node(NodeBuilder::new(0, TextContent("héllo wörld".to_string())).build()));
+
+    assert!(!weave.split_node(&0, 2, 1));
+    assert!(!weave.split_node_multi(&0, &[3, 9], &[1, 2]));
+    assert_eq!(weave.len(), 1);
+    assert_eq!(
+        weave.get_node(&0).unwrap().contents,
+        TextContent("héllo wörld".to_string())
+    );
+
+    assert!(weave.split_node_multi(&0, &[3, 7], &[1, 2]));
+    assert!(weave.split_node(&2, 3, 3));
+    assert_eq!(
+        [0, 1, 2, 3].map(|id| weave.get_node(&id).unwrap().contents.0.clone()),
+        ["hé", "llo ", "wö", "rld"]
+    );
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct MinLengthContent(u32);
+
+impl DiscreteContents for MinLengthContent {
+    fn split(self, at: usize) -> DiscreteContentResult<Self> {
+        let at = at as u32;
+
+        if at == 0 || at >= self.0 {
+            DiscreteContentResult::One(self)
+        } else {
+            DiscreteContentResult::Two(MinLengthContent(at), MinLengthContent(self.0 - at))
+        }
+    }
+    fn can_split_at(&self, at: usize) -> bool {
+        at >= 3 && (self.0 as usize).saturating_sub(at) >= 3
+    }
+    fn merge(self, value: Self) -> DiscreteContentResult<Self> {
+        DiscreteContentResult::One(MinLengthContent(self.0 + value.0))
+    }
+}
+
+#[test]
+fn split_node_multi_checks_remainder() {
+    let mut weave: DependentWeave<u32, MinLengthContent, (), RandomState> =
+        DependentWeave::with_capacity(3, ());
+
+    assert!(weave.add_node(NodeBuilder::new(0, MinLengthContent(10)).build()));
+
+    // Both indexes are valid for the original contents, but 5 leaves a piece of length 2
+    assert!(!weave.split_node_multi(&0, &[3, 5], &[1, 2]));
+    assert_eq!(weave.len(), 1);
+    assert_eq!(weave.get_node(&0).unwrap().contents, MinLengthContent(10));
+
+    assert!(weave.split_node_multi(&0, &[3, 6], &[1, 2]));
+    assert_eq!(
+        [0, 1, 2].map(|id| weave.get_node(&id).unwrap().contents.0),
+        [3, 3, 4]
+    );
+}
+
+#[test]
+fn merge_chain() {
+    let mut weave: DependentWeave<u32, WeaveContent, (), RandomState> =
+        DependentWeave::with_capacity(6, ());
+
+    for (id, content_seed, from) in [
+        (0, 1, None),
+        (1, 0, Some(0)),
+        (2, 0, Some(1)),
+        (3, 0, Some(2)),
+        (4, 0, Some(3)),
+        (5, 0, Some(3)),
+    ] {
+        assert!(weave.add_node(DependentNode {
+            id,
+            from,
+            to: IndexSet::default(),
+            active: false,
+            bookmarked: false,
+            contents: WeaveContent {
+                length: 1,
+                content_seed,
+            },
+        }));
+    }
+
+    assert_eq!(weave.merge_chain(&3), Some(1));
+    assert_eq!(weave.len(), 4);
+    assert_eq!(weave.get_node(&1).unwrap().contents.length, 3);
+    assert_eq!(weave.get_node(&1).unwrap().from, Some(0));
+    assert!(weave.get_node(&1).unwrap().to.iter().eq([&4, &5]));
+
+    assert_eq!(weave.merge_chain(&1), None);
+    assert_eq!(weave.merge_chain(&6), None);
+    assert_eq!(weave.len(), 4);
+}
+
+#[cfg(feature = "rkyv")]
+#[test]
+fn archived_to_owned() {
+    use universal_weave::dependent::ArchivedDependentWeave;
+
+    let mut weave: DependentWeave<u32, WeaveContent, u32, RandomState> =
+        DependentWeave::with_capacity(4, 7);
+
+    for (id, from) in [(0, None), (1, Some(0)), (2, Some(0)), (3, Some(2))] {
+        assert!(weave.add_node(DependentNode {
+            id,
+            from,
+            to: IndexSet::default(),
+            active: id == 3,
+            bookmarked: id == 1,
+            contents: WeaveContent {
+                length: 1,
+                content_seed: id,
+            },
+        }));
+    }
+
+    let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&weave).unwrap();
+    let archived = rkyv::access::<
+        ArchivedDependentWeave<u32, WeaveContent, u32, RandomState>,
+        rkyv::rancor::Error,
+    >(&bytes)
+    .unwrap();
+    let mut owned = archived.to_owned_weave().unwrap();
+    drop(bytes);
+
+    assert_eq!(owned.nodes(), weave.nodes());
+    assert_eq!(owned.roots(), weave.roots());
+    assert_eq!(owned.bookmarks(), weave.bookmarks());
+    assert_eq!(owned.metadata(), &7);
+    assert_eq!(owned.active(), Some(3));
+    assert!(
+        owned.add_node(
+            NodeBuilder::new(
+                4,
+                WeaveContent {
+                    length: 1,
+                    content_seed: 4,
+                }
+            )
+            .parent(1)
+            .build()
+        )
+    );
+}
+
+#[test]
+fn search_contents() {
+    let mut weave: DependentWeave<u32, WeaveContent, (), RandomState> =
+        DependentWeave::with_capacity(4, ());
+
+    for (id, from, content_seed) in [(0, None, 1), (1, Some(0), 2), (2, Some(1), 1), (3, None, 2)] {
+        assert!(weave.add_node(DependentNode {
+            id,
+            from,
+            to: IndexSet::default(),
+            active: false,
+            bookmarked: false,
+            contents: WeaveContent {
+                length: 1,
+                content_seed,
+            },
+        }));
+    }
+
+    let mut found: Vec<u32> = weave
+        .search_contents(|contents| contents.content_seed == 2)
+        .collect();
+    found.sort();
+    assert_eq!(found, [1, 3]);
+}
+
+#[test]
+fn try_split_node() {
+    let mut weave: DependentWeave<u32, TextContent, (), RandomState> =
+        DependentWeave::with_capacity(4, ());
+
+    assert!(weave.add_node(NodeBuilder::new(0, TextContent("héllo".to_string())).build()));
+    assert!(weave.add_node(NodeBuilder::new(1, TextContent("wörld".to_string())).build()));
+
+    assert_eq!(
+        weave.try_split_node(&5, 1, 2),
+        Err(SplitError::NodeNotFound)
+    );
+    assert_eq!(
+        weave.try_split_node(&0, 1, 1),
+        Err(SplitError::IdentifierInUse)
+    );
+    assert_eq!(
+        weave.try_split_node(&0, 1, 0),
+        Err(SplitError::IdentifierInUse)
+    );
+    assert_eq!(
+        weave.try_split_node(&0, 2, 2),
+        Err(SplitError::InvalidIndex)
+    );
+    assert_eq!(weave.try_split_node(&0, 6, 2), Err(SplitError::SplitFailed));
+    assert_eq!(weave.try_split_node(&0, 0, 2), Err(SplitError::SplitFailed));
+    assert_eq!(weave.len(), 2);
+
+    assert_eq!(weave.try_split_node(&0, 3, 2), Ok(()));
+    assert_eq!(
+        [0, 2].map(|id| weave.get_node(&id).unwrap().contents.0.clone()),
+        ["hé", "llo"]
+    );
+}
+
+#[test]
+fn merge_duplicate_children() {
+    let mut weave: DependentWeave<u32, TextContent, (), RandomState> =
+        DependentWeave::with_capacity(8, ());
+
+    for (id, text, from) in [
+        (0, "a", None),
+        (1, "b", Some(0)),
+        (2, "c", Some(0)),
+        (3, "b", Some(0)),
+        (4, "b", Some(0)),
+        (5, "d", Some(3)),
+        (6, "e", Some(4)),
+        (7, "b", Some(1)),
+    ] {
+        let mut builder = NodeBuilder::new(id, TextContent(text.to_string()));
+        if let Some(from) = from {
+            builder = builder.parent(from);
+        }
+        assert!(weave.add_node(builder.build()));
+    }
+    assert!(weave.set_node_bookmarked_status(&4, true));
+    assert!(weave.set_node_active_status(&3, true, false));
+
+    assert_eq!(weave.merge_duplicate_children(&8), 0);
+    assert_eq!(weave.merge_duplicate_children(&0), 2);
+    assert_eq!(weave.len(), 6);
+    assert!(weave.get_node(&0).unwrap().to.iter().eq([&1, &2]));
+    assert!(weave.get_node(&1).unwrap().to.iter().eq([&7, &5, &6]));
+    assert_eq!(weave.get_node(&5).unwrap().from, Some(1));
+    assert_eq!(weave.get_node(&6).unwrap().from, Some(1));
+    assert!(weave.bookmarks().iter().eq([&1]));
+    assert_eq!(weave.active(), Some(1));
+
+    assert_eq!(weave.merge_duplicate_children(&0), 0);
+}
+
+#[cfg(feature = "rkyv")]
+#[derive(rkyv::Archive, rkyv::Deserialize, rkyv::Serialize)]
+struct ByteContent(Vec<u8>);
+
+#[cfg(feature = "rkyv")]
+impl AsRef<[u8]> for ArchivedByteContent {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_slice()
+    }
+}
+
+#[cfg(feature = "rkyv")]
+#[test]
+fn archived_active_content() {
+    use universal_weave::{ArchivedWeave, dependent::ArchivedDependentWeave};
+
+    let mut weave: DependentWeave<u32, ByteContent, (), RandomState> =
+        DependentWeave::with_capacity(4, ());
+
+    for (id, from, bytes) in [
+        (0, None, "Hello"),
+        (1, Some(0), ", world"),
+        (2, Some(0), " there"),
+        (3, Some(2), "!"),
+    ] {
+        let mut builder = NodeBuilder::new(id, ByteContent(bytes.as_bytes().to_vec()));
+        if let Some(from) = from {
+            builder = builder.parent(from);
+        }
+        assert!(weave.add_node(builder.build()));
+    }
+
+    let mut content = vec![0];
+    let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&weave).unwrap();
+    let archived = rkyv::access::<
+        ArchivedDependentWeave<u32, ByteContent, (), RandomState>,
+        rkyv::rancor::Error,
+    >(&bytes)
+    .unwrap();
+    archived.get_active_content(&mut content);
+    assert!(content.is_empty());
+
+    assert!(weave.set_node_active_status(&3, true, false));
+    let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&weave).unwrap();
+    let archived = rkyv::access::<
+        ArchivedDependentWeave<u32, ByteContent, (), RandomState>,
+        rkyv::rancor::Error,
+    >(&bytes)
+    .unwrap();
+    archived.get_active_content(&mut content);
+    assert_eq!(content, b"Hello there!");
+}
@@ -1,7 +1,9 @@
 use std::{
+    cell::RefCell,
     collections::{HashMap, HashSet},
-    hash::{BuildHasher, Hash, RandomState},
+    hash::{BuildHasher, BuildHasherDefault, Hash, Hasher, RandomState},
     ops::Index,
+    rc::Rc,
 };
 
 use indexmap::IndexSet;
@@ -10,10 +12,12 @@ use proptest_derive::Arbitrary;
 use proptest_state_machine::{ReferenceStateMachine, StateMachineTest, prop_state_machine};
 use stacksafe::stacksafe;
 use universal_weave::{
-    DiscreteContentResult, DiscreteContents, DiscreteWeave, IndependentContents,
-    IndependentWeave as IndependentWeaveTrait, MetadataWeave, Node, SemiIndependentWeave,
-    SortableWeave, Weave,
-    independent::{IndependentNode, IndependentWeave},
+    ActivePathWeave, DeduplicatableContents, DeduplicatableWeave, DiscreteContentResult,
+    DiscreteContents, DiscreteWeave, HashableContents, IndependentContents,
+    IndependentWeave as IndependentWeaveTrait, MetadataWeave, Node, NodeBuilder,
+    SemiIndependentWeave, SortableWeave, SplitError, TimestampedNode, Weave, WeaveError,
+    independent::{IndependentNode, IndependentWeave, NodeStyle},
+    wrappers::{ActionableWeave, ObservedWeave, WeaveAction, WeaveEvent},
 };
 
 const CASES: u32 = 16384;
@@ -104,6 +108,18 @@ enum WeaveTransition {
         value: bool,
         id_seed: u32,
     },
+    BookmarkMatching {
+        value: bool,
+        content_seed: u32,
+    },
+    MakeSoleActiveChild {
+        id_seed: u32,
+        child_seed: u32,
+    },
+    MoveAllChildren {
+        from_seed: u32,
+        to_seed: u32,
+    },
     #[proptest(weight = 3)]
     RemoveNode {
         id_seed: u32,
@@ -115,6 +131,21 @@ enum WeaveTransition {
     RemoveAllNodes {
         apply_seed: u16,
     },
+    Clear {
+        apply_seed: u16,
+    },
+    ClearActive {
+        apply_seed: u8,
+    },
+    ActivateToLeaf {
+        id_seed: u32,
+    },
+    PruneToActive {
+        apply_seed: u8,
+    },
+    Retain {
+        content_seed: u32,
+    },
     MetadataMut {
         content_seed: u32,
     },
@@ -138,6 +169,14 @@ enum WeaveTransition {
     SortBookmarksById {
         sort_seed: u32,
     },
+    MoveChildToIndex {
+        id_seed: u32,
+        index: u8,
+    },
+    MoveRootToIndex {
+        id_seed: u32,
+        index: u8,
+    },
     #[proptest(weight = 3)]
     MoveNode {
         #[proptest(strategy = "any_with::<Vec<u32>>((size_range(0..=3), ()))")]
@@ -148,15 +187,38 @@ enum WeaveTransition {
         id_seed: u32,
         content_seed: u32,
     },
+    SetContents {
+        id_seed: u32,
+        content_seed: u32,
+    },
+    WithNodeMut {
+        id_seed: u32,
+        content_seed: u32,
+        active: bool,
+        bookmarked: bool,
+    },
     #[proptest(weight = 3)]
     SplitNode {
         at_seed: u32,
         id_seed: u32,
     },
+    SplitNodeMulti {
+        at_seeds: (u32, u32),
+        id_seed: u32,
+    },
     #[proptest(weight = 3)]
     MergeNodeWithParent {
         id_seed: u32,
     },
+    MergeChain {
+        id_seed: u32,
+    },
+    DeduplicateAll,
+    MergeDuplicateChildren {
+        id_seed: u32,
+    },
+    Snapshot,
+    DiffSnapshot,
 }
 
 struct WeaveWrapper {
@@ -164,9 +226,14 @@ struct WeaveWrapper {
     counter: u32,
     scratchpad: Vec<u32>,
     scratchpad_set: HashSet<u32>,
+    snapshot: Option<IndependentWeave<u32, WeaveContent, u32, RandomState>>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Deserialize, rkyv::Serialize)
+)]
 struct WeaveContent {
     length: u32,
     content_seed: u32,
@@ -174,6 +241,14 @@ struct WeaveContent {
 
 impl IndependentContents for WeaveContent {}
 
+impl DeduplicatableContents for WeaveContent {
+    fn is_duplicate_of(&self, other: &Self) -> bool {
+        self == other
+    }
+}
+
+impl HashableContents for WeaveContent {}
+
 impl DiscreteContents for WeaveContent {
     fn split(self, at: usize) -> DiscreteContentResult<Self> {
         if at == 0 || at as u64 >= self.length as u64 {
@@ -206,6 +281,12 @@ impl DiscreteContents for WeaveContent {
     }
 }
 
+impl TimestampedNode<u32, WeaveContent> for IndependentNode<u32, WeaveContent, RandomState> {
+    fn created_at(&self) -> Option<u64> {
+        (self.contents.length > 0).then_some(u64::from(self.contents.content_seed))
+    }
+}
+
 // Invariants are validated by the function's contracts
 impl StateMachineTest for WeaveWrapper {
     type SystemUnderTest = Self;
@@ -220,6 +301,7 @@ impl StateMachineTest for WeaveWrapper {
             counter: 0,
             scratchpad: Vec::with_capacity(ref_state.len()),
             scratchpad_set: HashSet::with_capacity(ref_state.len()),
+            snapshot: None,
         }
     }
     fn apply(
@@ -273,6 +355,21 @@ impl StateMachineTest for WeaveWrapper {
             WeaveTransition::GetActiveThread => {
                 println!("weave.get_active_thread(&mut scratchpad);");
                 state.weave.get_active_thread(&mut state.scratchpad);
+                assert_eq!(state.weave.active_thread_vec(), state.scratchpad);
+
+                let mut index = 0;
+                state.weave.for_each_active(|node| {
+                    assert_eq!(state.scratchpad[index], node.id);
+                    index += 1;
+                });
+                assert_eq!(index, state.scratchpad.len());
+
+                for id in state.weave.nodes().keys() {
+                    assert_eq!(
+                        state.weave.is_on_active_thread(id),
+                        state.scratchpad.contains(id)
+                    );
+                }
             }
             WeaveTransition::GetThreadFrom { id_seed } => {
                 println!(
@@ -282,6 +379,10 @@ impl StateMachineTest for WeaveWrapper {
                 state
                     .weave
                     .get_thread_from(&map_id(id_seed), &mut state.scratchpad);
+                assert_eq!(
+                    state.weave.thread_from_vec(&map_id(id_seed)),
+                    state.scratchpad
+                );
             }
             WeaveTransition::AddNode {
                 from_seeds,
@@ -300,9 +401,10 @@ impl StateMachineTest for WeaveWrapper {
                         length: length % 64,
                         content_seed: content_seed % 4,
                     },
+                    style: None,
                 };
                 println!(
-                    "weave.add_node(IndependentNode {{ id: {}, from: IndexSet::from_iter({:?}), to: IndexSet::default(), active: {}, bookmarked: {}, contents:  WeaveContent {{ length: {}, content_seed: {} }} }});",
+                    "weave.add_node(IndependentNode {{ id: {}, from: IndexSet::from_iter({:?}), to: IndexSet::default(), active: {}, bookmarked: {}, contents:  WeaveContent {{ length: {}, content_seed: {} }}, style: None }});",
                     node.id,
                     node.from.iter().copied().collect::<Vec<_>>(),
                     node.active,
@@ -350,9 +452,10 @@ impl StateMachineTest for WeaveWrapper {
                         length: length % 64,
                         content_seed: content_seed % 4,
                     },
+                    style: None,
                 };
                 println!(
-                    "weave.add_node(IndependentNode {{ id: {}, from: IndexSet::from_iter({:?}), to: IndexSet::from_iter({:?}), active: {}, bookmarked: {}, contents:  WeaveContent {{ length: {}, content_seed: {} }} }});",
+                    "weave.add_node(IndependentNode {{ id: {}, from: IndexSet::from_iter({:?}), to: IndexSet::from_iter({:?}), active: {}, bookmarked: {}, contents:  WeaveContent {{ length: {}, content_seed: {} }}, style: None }});",
                     node.id,
                     node.from.iter().copied().collect::<Vec<_>>(),
                     node.to.iter().copied().collect::<Vec<_>>(),
@@ -388,6 +491,32 @@ impl StateMachineTest for WeaveWrapper {
                     .weave
                     .set_node_active_status_in_place(&map_id(id_seed), value);
             }
+            WeaveTransition::MakeSoleActiveChild {
+                id_seed,
+                child_seed,
+            } => {
+                let parent = map_id(id_seed);
+                let child = state.weave.get_node(&parent).and_then(|node| {
+                    node.to
+                        .get_index(child_seed as usize % node.to.len().max(1))
+                        .copied()
+                });
+
+                if let Some(child) = child {
+                    println!("weave.make_sole_active_child(&{}, &{});", parent, child);
+                    assert!(state.weave.make_sole_active_child(&parent, &child));
+                }
+            }
+            WeaveTransition::MoveAllChildren { from_seed, to_seed } => {
+                println!(
+                    "weave.move_all_children(&{}, &{});",
+                    map_id(from_seed),
+                    map_id(to_seed)
+                );
+                state
+                    .weave
+                    .move_all_children(&map_id(from_seed), &map_id(to_seed));
+            }
             WeaveTransition::SetNodeBookmarkedStatus { id_seed, value } => {
                 println!(
                     "weave.set_node_bookmarked_status(&{}, {});",
@@ -398,6 +527,28 @@ impl StateMachineTest for WeaveWrapper {
                     .weave
                     .set_node_bookmarked_status(&map_id(id_seed), value);
             }
+            WeaveTransition::BookmarkMatching {
+                value,
+                content_seed,
+            } => {
+                if value {
+                    println!(
+                        "weave.bookmark_matching(|n| n.contents.content_seed == {});",
+                        content_seed % 4
+                    );
+                    state
+                        .weave
+                        .bookmark_matching(|n| n.contents.content_seed == content_seed % 4);
+                } else {
+                    println!(
+                        "weave.unbookmark_matching(|n| n.contents.content_seed == {});",
+                        content_seed % 4
+                    );
+                    state
+                        .weave
+                        .unbookmark_matching(|n| n.contents.content_seed == content_seed % 4);
+                }
+            }
             WeaveTransition::RemoveNode { id_seed } => {
                 println!("weave.remove_node(&{});", map_id(id_seed));
                 state.weave.remove_node(&map_id(id_seed));
@@ -415,6 +566,62 @@ impl StateMachineTest for WeaveWrapper {
                     state.weave.remove_all_nodes();
                 }
             }
+            WeaveTransition::Retain { content_seed } => {
+                println!(
+                    "weave.retain(|n| n.contents.content_seed != {});",
+                    content_seed % 4
+                );
+                state
+                    .weave
+                    .retain(|n| n.contents.content_seed != content_seed % 4);
+            }
+            WeaveTransition::Clear { apply_seed } => {
+                if apply_seed == 0 {
+                    println!("weave.clear();");
+                    let metadata = *state.weave.metadata();
+                    state.weave.clear();
+                    assert!(state.weave.is_empty());
+                    assert_eq!(*state.weave.metadata(), metadata);
+                }
+            }
+            WeaveTransition::ActivateToLeaf { id_seed } => {
+                println!("weave.activate_to_leaf(&{});", map_id(id_seed));
+                let id = map_id(id_seed);
+                let mut leaf = state.weave.get_node(&id).map(|node| node.id);
+                while let Some(child) = leaf
+                    .and_then(|leaf| state.weave.get_node(&leaf))
+                    .and_then(|node| node.to.first())
+                {
+                    leaf = Some(*child);
+                }
+
+                assert_eq!(state.weave.activate_to_leaf(&id), leaf.is_some());
+
+                if let Some(leaf) = leaf {
+                    let thread = state.weave.active_thread_vec();
+                    assert_eq!(thread.first(), Some(&leaf));
+                    assert!(thread.contains(&id));
+                }
+            }
+            WeaveTransition::PruneToActive { apply_seed } => {
+                if apply_seed % 16 == 0 {
+                    println!("weave.prune_to_active();");
+                    let thread = state.weave.active_thread_vec();
+                    state.weave.prune_to_active();
+                    assert_eq!(state.weave.active_thread_vec(), thread);
+                    assert_eq!(state.weave.len(), thread.len());
+                }
+            }
+            WeaveTransition::ClearActive { apply_seed } => {
+                if apply_seed % 8 == 0 {
+                    println!("weave.clear_active();");
+                    let len = state.weave.len();
+                    state.weave.clear_active();
+                    assert_eq!(state.weave.count_active(), 0);
+                    assert!(state.weave.active_thread_vec().is_empty());
+                    assert_eq!(state.weave.len(), len);
+                }
+            }
             WeaveTransition::MetadataMut { content_seed } => {
                 println!("weave.metadata_mut(|m| *m = {});", content_seed);
                 state.weave.metadata_mut(|m| *m = content_seed);
@@ -455,6 +662,53 @@ impl StateMachineTest for WeaveWrapper {
                     hash_value(a.id as u64 + sort_seed).cmp(&hash_value(b.id as u64 + sort_seed))
                 });
             }
+            WeaveTransition::MoveChildToIndex { id_seed, index } => {
+                let child = map_id(id_seed);
+                let parent = state
+                    .weave
+                    .get_node(&child)
+                    .and_then(|node| node.from.first().copied());
+
+                if let Some(parent) = parent {
+                    println!(
+                        "weave.move_child_to_index(&{}, &{}, {});",
+                        parent, child, index
+                    );
+                    assert!(
+                        state
+                            .weave
+                            .move_child_to_index(&parent, &child, index as usize)
+                    );
+                    let siblings = &state.weave.get_node(&parent).unwrap().to;
+                    assert_eq!(
+                        siblings.get_index_of(&child),
+                        Some((index as usize).min(siblings.len() - 1))
+                    );
+                } else {
+                    println!(
+                        "weave.move_child_to_index(&{}, &{}, {});",
+                        child, child, index
+                    );
+                    assert!(
+                        !state
+                            .weave
+                            .move_child_to_index(&child, &child, index as usize)
+                    );
+                }
+            }
+            WeaveTransition::MoveRootToIndex { id_seed, index } => {
+                let id = map_id(id_seed);
+                println!("weave.move_root_to_index(&{}, {});", id, index);
+
+                let moved = state.weave.move_root_to_index(&id, index as usize);
+                assert_eq!(moved, state.weave.roots().contains(&id));
+                if moved {
+                    assert_eq!(
+                        state.weave.roots().get_index_of(&id),
+                        Some((index as usize).min(state.weave.roots().len() - 1))
+                    );
+                }
+            }
             WeaveTransition::SortRootsById { sort_seed } => {
                 let sort_seed = sort_seed as u64;
                 println!(
@@ -522,6 +776,66 @@ impl StateMachineTest for WeaveWrapper {
                     .weave
                     .get_contents_mut(&map_id(id_seed), |c| c.content_seed = content_seed % 4);
             }
+            WeaveTransition::SetContents {
+                id_seed,
+                content_seed,
+            } => {
+                let id = map_id(id_seed);
+                let length = state.weave.get_node(&id).map(|node| node.contents.length);
+                println!(
+                    "weave.set_contents(&{}, WeaveContent {{ length: {}, content_seed: {} }});",
+                    id,
+                    length.unwrap_or(1),
+                    content_seed % 4
+                );
+                let found = state.weave.set_contents(
+                    &id,
+                    WeaveContent {
+                        length: length.unwrap_or(1),
+                        content_seed: content_seed % 4,
+                    },
+                );
+                assert_eq!(found, length.is_some());
+
+                if found {
+                    assert_eq!(
+                        state.weave.get_node(&id).unwrap().contents.content_seed,
+                        content_seed % 4
+                    );
+                }
+            }
+            WeaveTransition::WithNodeMut {
+                id_seed,
+                content_seed,
+                active,
+                bookmarked,
+            } => {
+                println!(
+                    "weave.with_node_mut(&{}, |n| {{ n.active = {active}; n.bookmarked = {bookmarked}; n.contents.content_seed = {}; }});",
+                    map_id(id_seed),
+                    content_seed % 4
+                );
+                let id = map_id(id_seed);
+                let old_node = state.weave.get_node(&id).cloned();
+
+                let found = state.weave.with_node_mut(&id, |node| {
+                    node.from.clear();
+                    node.to.clear();
+                    node.active = active;
+                    node.bookmarked = bookmarked;
+                    node.contents.content_seed = content_seed % 4;
+                });
+                assert_eq!(found, old_node.is_some());
+
+                if let Some(old_node) = old_node {
+                    let node = state.weave.get_node(&id).unwrap();
+                    assert_eq!(node.from, old_node.from);
+                    assert_eq!(node.to, old_node.to);
+                    assert_eq!(state.weave.contains_active(&id), active);
+                    assert_eq!(state.weave.contains_bookmark(&id), bookmarked);
+                    assert_eq!(node.contents.content_seed, content_seed % 4);
+                }
+            }
             WeaveTransition::SplitNode { id_seed, at_seed } => {
                 let split_at = state
                     .weave
@@ -538,14 +852,100 @@ impl StateMachineTest for WeaveWrapper {
                     split_at,
                     state.counter
                 );
-                state
+                let expected = if !state.weave.contains(&map_id(id_seed)) {
+                    Err(SplitError::NodeNotFound)
+                } else if state.weave.contains(&state.counter) {
+                    Err(SplitError::IdentifierInUse)
+                } else if split_at == 0 {
+                    Err(SplitError::SplitFailed)
+                } else {
+                    Ok(())
+                };
+                assert_eq!(
+                    state
+                        .weave
+                        .try_split_node(&map_id(id_seed), split_at, state.counter),
+                    expected
+                );
+            }
+            WeaveTransition::SplitNodeMulti { id_seed, at_seeds } => {
+                let id = map_id(id_seed);
+                let ats: Vec<usize> = state
                     .weave
-                    .split_node(&map_id(id_seed), split_at, state.counter);
+                    .get_node(&id)
+                    .map(|node| {
+                        [at_seeds.0, at_seeds.1]
+                            .into_iter()
+                            .map(|seed| {
+                                seed.checked_rem(node.contents.length).unwrap_or_default() as usize
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                let new_ids = [state.counter, state.counter + 1];
+                println!(
+                    "weave.split_node_multi(&{}, &{:?}, &{:?});",
+                    map_id(id_seed),
+                    ats,
+                    new_ids
+                );
+                let split = state.weave.split_node_multi(&id, &ats, &new_ids);
+
+                if split {
+                    assert!(ats.is_sorted_by(|a, b| a < b));
+                    assert!(
+                        state
+                            .weave
+                            .get_node(&id)
+                            .unwrap()
+                            .to
+                            .iter()
+                            .eq([&new_ids[0]])
+                    );
+                    state.counter += 1;
+                }
             }
             WeaveTransition::MergeNodeWithParent { id_seed } => {
                 println!("weave.merge_with_parent(&{});", map_id(id_seed));
                 state.weave.merge_with_parent(&map_id(id_seed));
             }
+            WeaveTransition::MergeChain { id_seed } => {
+                println!("weave.merge_chain(&{});", map_id(id_seed));
+                state.weave.merge_chain(&map_id(id_seed));
+            }
+            WeaveTransition::DeduplicateAll => {
+                println!("weave.deduplicate_all();");
+                let old_len = state.weave.len();
+                let removed = state.weave.deduplicate_all();
+                assert_eq!(old_len - removed, state.weave.len());
+            }
+            WeaveTransition::MergeDuplicateChildren { id_seed } => {
+                println!("weave.merge_duplicate_children(&{});", map_id(id_seed));
+                let id = map_id(id_seed);
+                let old_len = state.weave.len();
+                let had_active = state.weave.count_active() > 0;
+                let removed = state.weave.merge_duplicate_children(&id);
+                assert_eq!(old_len - removed, state.weave.len());
+                assert_eq!(state.weave.count_active() > 0, had_active);
+            }
+            WeaveTransition::Snapshot => {
+                println!("let snapshot = weave.clone();");
+                state.snapshot = Some(state.weave.clone());
+            }
+            WeaveTransition::DiffSnapshot => {
+                if let Some(mut snapshot) = state.snapshot.take() {
+                    println!("for action in snapshot.diff(&weave) {{ snapshot.apply(action); }}");
+                    let actions = snapshot.diff(&state.weave);
+                    let mut applied = snapshot.clone();
+                    assert!(applied.apply_actions(&actions));
+                    for action in actions {
+                        snapshot.apply(action);
+                    }
+                    assert_same_weave(&snapshot, &state.weave);
+                    assert_same_weave(&applied, &state.weave);
+                    assert!(snapshot.diff(&state.weave).is_empty());
+                }
+            }
         }
         if state.weave.nodes().len() > old_node_count {
             state.counter += 1;
@@ -553,12 +953,33 @@ impl StateMachineTest for WeaveWrapper {
         state
     }
     fn check_invariants(
-        _state: &Self::SystemUnderTest,
+        state: &Self::SystemUnderTest,
         _ref_state: &<Self::Reference as ReferenceStateMachine>::State,
     ) {
+        let nodes = state.weave.nodes().values();
+
+        assert_eq!(
+            state.weave.count_active(),
+            nodes.clone().filter(|node| node.active).count()
+        );
+        assert_eq!(
+            state.weave.count_bookmarked(),
+            nodes.filter(|node| node.bookmarked).count()
+        );
     }
 }
 
+fn assert_same_weave(
+    a: &IndependentWeave<u32, WeaveContent, u32, RandomState>,
+    b: &IndependentWeave<u32, WeaveContent, u32, RandomState>,
+) {
+    assert_eq!(a.nodes(), b.nodes());
+    assert!(a.roots().iter().eq(b.roots()));
+    assert!(a.bookmarks().iter().eq(b.bookmarks()));
+    assert_eq!(a.active(), b.active());
+    assert_eq!(a.metadata(), b.metadata());
+}
+
 // Copied from src/lib.rs
 #[stacksafe]
 fn topological_sort<'a, K, N, T, S>(
@@ -672,6 +1093,1956 @@ where
     (identifiers, identifier_set)
 }
 
+#[cfg(feature = "rkyv")]
+#[test]
+fn node_style_round_trip() {
+    let mut weave: IndependentWeave<u32, WeaveContent, u32, RandomState> =
+        IndependentWeave::with_capacity(2, 0);
+
+    for (id, from) in [(0, None), (1, Some(0))] {
+        assert!(weave.add_node(IndependentNode {
+            id,
+            from: IndexSet::from_iter(from),
+            to: IndexSet::default(),
+            active: true,
+            bookmarked: false,
+            contents: WeaveContent {
+                length: 1,
+                content_seed: 0,
+            },
+            style: None,
+        }));
+    }
+
+    assert_eq!(weave.get_node_style(&0), None);
+    assert_eq!(weave.get_node_style(&1), None);
+
+    let style = NodeStyle {
+        color: [255, 0, 0, 255],
+        collapsed: true,
+    };
+    assert!(weave.set_node_style(&1, Some(style)));
+    assert!(!weave.set_node_style(&2, Some(style)));
+
+    let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&weave).unwrap();
+    let weave = rkyv::from_bytes::<
+        IndependentWeave<u32, WeaveContent, u32, RandomState>,
+        rkyv::rancor::Error,
+    >(&bytes)
+    .unwrap();
+
+    assert_eq!(weave.get_node_style(&0), None);
+    assert_eq!(weave.get_node_style(&1), Some(style));
+}
+
+#[test]
+fn activate_over_bypassing_thread() {
+    let mut weave: IndependentWeave<u32, WeaveContent, u32, RandomState> =
+        IndependentWeave::with_capacity(5, 0);
+
+    for (id, from, active) in [
+        (0, vec![], true),
+        (1, vec![0], true),
+        (2, vec![1], false),
+        (3, vec![0, 2], false),
+        (4, vec![0, 3], true),
+    ] {
+        assert!(weave.add_node(IndependentNode {
+            id,
+            from: IndexSet::from_iter(from),
+            to: IndexSet::default(),
+            active,
+            bookmarked: false,
+            contents: WeaveContent {
+                length: 1,
+                content_seed: 0,
+            },
+            style: None,
+        }));
+    }
+
+    let mut thread = Vec::new();
+    weave.get_active_thread(&mut thread);
+    assert_eq!(thread, vec![4, 3, 2, 1, 0]);
+
+    assert!(weave.set_node_active_status(&1, true, false));
+    assert!(weave.contains_active(&1));
+    assert!(!weave.contains_active(&2));
+
+    thread.clear();
+    weave.get_active_thread(&mut thread);
+    assert_eq!(thread, vec![1, 0]);
+}
+
+#[test]
+fn activate_in_place_over_previous_thread() {
+    let mut weave: IndependentWeave<u32, WeaveContent, u32, RandomState> =
+        IndependentWeave::with_capacity(7, 0);
+
+    for (id, from, active) in [
+        (0, vec![], true),
+        (1, vec![0], false),
+        (2, vec![1], false),
+        (3, vec![2], false),
+        (4, vec![0, 3], true),
+        (5, vec![4], true),
+        (6, vec![5, 2], true),
+    ] {
+        assert!(weave.add_node(IndependentNode {
+            id,
+            from: IndexSet::from_iter(from),
+            to: IndexSet::default(),
+            active,
+            bookmarked: false,
+            contents: WeaveContent {
+                length: 1,
+                content_seed: 0,
+            },
+            style: None,
+        }));
+    }
+
+    let mut thread = Vec::new();
+    weave.get_active_thread(&mut thread);
+    assert_eq!(thread, vec![6, 5, 4, 0]);
+
+    assert!(weave.set_node_active_status_in_place(&2, true));
+    assert!(!weave.contains_active(&4) && !weave.contains_active(&5));
+
+    thread.clear();
+    weave.get_active_thread(&mut thread);
+    assert_eq!(thread, vec![6, 2, 1, 0]);
+}
+
+#[test]
+fn sole_active_child() {
+    let mut weave: IndependentWeave<u32, WeaveContent, u32, RandomState> =
+        IndependentWeave::with_capacity(4, 0);
+
+    for (id, from) in [(0, None), (1, Some(0)), (2, Some(0)), (3, Some(1))] {
+        assert!(weave.add_node(IndependentNode {
+            id,
+            from: IndexSet::from_iter(from),
+            to: IndexSet::default(),
+            active: id == 3,
+            bookmarked: false,
+            contents: WeaveContent {
+                length: 1,
+                content_seed: 0,
+            },
+            style: None,
+        }));
+    }
+
+    assert!(weave.contains_active(&1) && weave.contains_active(&3));
+
+    assert!(weave.make_sole_active_child(&0, &2));
+    assert!(weave.contains_active(&0) && weave.contains_active(&2));
+    assert!(!weave.contains_active(&1) && !weave.contains_active(&3));
+
+    assert!(weave.make_sole_active_child(&0, &2));
+    assert!(weave.contains_active(&2));
+
+    assert!(!weave.make_sole_active_child(&1, &2));
+    assert!(!weave.make_sole_active_child(&4, &2));
+}
+
+#[test]
+fn node_depth() {
+    let mut weave: IndependentWeave<u32, WeaveContent, u32, RandomState> =
+        IndependentWeave::with_capacity(6, 0);
+
+    for (id, from) in [
+        (0, vec![]),
+        (1, vec![0]),
+        (2, vec![1]),
+        (3, vec![]),
+        (4, vec![2, 3]),
+        (5, vec![2, 4]),
+    ] {
+        assert!(weave.add_node(IndependentNode {
+            id,
+            from: IndexSet::from_iter(from),
+            to: IndexSet::default(),
+            active: false,
+            bookmarked: false,
+            contents: WeaveContent {
+                length: 1,
+                content_seed: 0,
+            },
+            style: None,
+        }));
+    }
+
+    assert_eq!(weave.get_depth(&0), Some(0));
+    assert_eq!(weave.get_depth(&2), Some(2));
+    assert_eq!(weave.get_depth(&4), Some(1));
+    assert_eq!(weave.get_depth(&5), Some(2));
+    assert_eq!(weave.get_depth(&6), None);
+
+    #[cfg(feature = "rkyv")]
+    {
+        use universal_weave::{ArchivedWeave, independent::ArchivedIndependentWeave};
+
+        let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&weave).unwrap();
+        let archived = rkyv::access::<
+            ArchivedIndependentWeave<u32, WeaveContent, u32, RandomState>,
+            rkyv::rancor::Error,
+        >(&bytes)
+        .unwrap();
+
+        for id in 0..7 {
+            assert_eq!(
+                archived.get_depth(&rkyv::rend::u32_le::from_native(id)),
+                weave.get_depth(&id)
+            );
+        }
+    }
+}
+
+#[test]
+fn node_ancestors() {
+    let mut weave: IndependentWeave<u32, WeaveContent, u32, RandomState> =
+        IndependentWeave::with_capacity(5, 0);
+
+    for (id, from) in [
+        (0, vec![]),
+        (1, vec![0]),
+        (2, vec![0]),
+        (3, vec![1, 2]),
+        (4, vec![3]),
+    ] {
+        assert!(weave.add_node(IndependentNode {
+            id,
+            from: IndexSet::from_iter(from),
+            to: IndexSet::default(),
+            active: false,
+            bookmarked: false,
+            contents: WeaveContent {
+                length: 1,
+                content_seed: 0,
+            },
+            style: None,
+        }));
+    }
+
+    assert_eq!(weave.ancestors(&4).collect::<Vec<_>>(), vec![3, 1, 2, 0]);
+    assert_eq!(weave.ancestors(&0).count(), 0);
+    assert_eq!(weave.ancestors(&5).count(), 0);
+}
+
+#[test]
+fn node_descendants() {
+    let mut weave: IndependentWeave<u32, WeaveContent, u32, RandomState> =
+        IndependentWeave::with_capacity(5, 0);
+
+    for (id, from) in [
+        (0, vec![]),
+        (1, vec![0]),
+        (2, vec![0]),
+        (3, vec![1, 2]),
+        (4, vec![3]),
+    ] {
+        assert!(weave.add_node(IndependentNode {
+            id,
+            from: IndexSet::from_iter(from),
+            to: IndexSet::default(),
+            active: false,
+            bookmarked: false,
+            contents: WeaveContent {
+                length: 1,
+                content_seed: 0,
+            },
+            style: None,
+        }));
+    }
+
+    assert_eq!(weave.descendants(&0).collect::<Vec<_>>(), vec![1, 3, 4, 2]);
+    assert_eq!(weave.descendants(&4).count(), 0);
+    assert_eq!(weave.descendants(&5).count(), 0);
+}
+
+#[test]
+fn leaf_nodes() {
+    let mut weave: IndependentWeave<u32, WeaveContent, u32, RandomState> =
+        IndependentWeave::with_capacity(7, 0);
+
+    for (id, from) in [
+        (0, vec![]),
+        (1, vec![0]),
+        (2, vec![0]),
+        (3, vec![1, 2]),
+        (4, vec![3]),
+        (5, vec![2]),
+        (6, vec![]),
+    ] {
+        assert!(weave.add_node(IndependentNode {
+            id,
+            from: IndexSet::from_iter(from),
+            to: IndexSet::default(),
+            active: false,
+            bookmarked: false,
+            contents: WeaveContent {
+                length: 1,
+                content_seed: 0,
+            },
+            style: None,
+        }));
+    }
+
+    assert_eq!(weave.leaves().collect::<Vec<_>>(), vec![4, 5, 6]);
+
+    #[cfg(feature = "rkyv")]
+    {
+        use universal_weave::{ArchivedWeave, independent::ArchivedIndependentWeave};
+
+        let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&weave).unwrap();
+        let archived = rkyv::access::<
+            ArchivedIndependentWeave<u32, WeaveContent, u32, RandomState>,
+            rkyv::rancor::Error,
+        >(&bytes)
+        .unwrap();
+
+        assert_eq!(
+            archived
+                .leaves()
+                .map(|id| id.to_native())
+                .collect::<Vec<_>>(),
+            vec![4, 5, 6]
+        );
+    }
+}
+
+#[test]
+fn move_all_children() {
+    let mut weave: IndependentWeave<u32, WeaveContent, u32, RandomState> =
+        IndependentWeave::with_capacity(6, 0);
+
+    for (id, from) in [
+        (0, vec![]),
+        (1, vec![0]),
+        (2, vec![0]),
+        (3, vec![1]),
+        (4, vec![1, 2]),
+        (5, vec![3]),
+    ] {
+        assert!(weave.add_node(IndependentNode {
+            id,
+            from: IndexSet::from_iter(from),
+            to: IndexSet::default(),
+            active: id == 5,
+            bookmarked: false,
+            contents: WeaveContent {
+                length: 1,
+                content_seed: 0,
+            },
+            style: None,
+        }));
+    }
+
+    assert!(!weave.move_all_children(&1, &5));
+    assert!(!weave.move_all_children(&1, &1));
+    assert!(!weave.move_all_children(&1, &6));
+
+    assert!(weave.move_all_children(&1, &2));
+    assert!(weave.get_node(&1).unwrap().to.is_empty());
+    assert_eq!(
+        weave
+            .get_node(&2)
+            .unwrap()
+            .to
+            .iter()
+            .copied()
+            .collect::<Vec<_>>(),
+        vec![4, 3]
+    );
+    assert_eq!(
+        weave
+            .get_node(&4)
+            .unwrap()
+            .from
+            .iter()
+            .copied()
+            .collect::<Vec<_>>(),
+        vec![2]
+    );
+    assert_eq!(weave.leaves().collect::<Vec<_>>(), vec![1, 4, 5]);
+}
+
+#[test]
+fn batch_insertion() {
+    let mut weave: IndependentWeave<u32, WeaveContent, u32, RandomState> =
+        IndependentWeave::with_capacity(5, 0);
+
+    let node = |id, from: Vec<u32>, to: Vec<u32>| IndependentNode {
+        id,
+        from: IndexSet::from_iter(from),
+        to: IndexSet::from_iter(to),
+        active: id == 3,
+        bookmarked: false,
+        contents: WeaveContent {
+            length: 1,
+            content_seed: 0,
+        },
+        style: None,
+    };
+
+    assert_eq!(
+        weave.add_nodes([
+            node(3, vec![1, 2], vec![]),
+            node(2, vec![0], vec![]),
+            node(1, vec![0], vec![]),
+            node(0, vec![], vec![]),
+            node(2, vec![], vec![]),
+            node(4, vec![5], vec![]),
+            node(6, vec![], vec![0]),
+        ]),
+        vec![true, true, true, true, false, false, true]
+    );
+    assert_eq!(weave.len(), 5);
+    assert!([6, 0, 3].iter().all(|id| weave.contains_active(id)));
+    assert_eq!(weave.roots().iter().copied().collect::<Vec<_>>(), vec![6]);
+}
+
+#[test]
+fn lowest_common_ancestor() {
+    let mut weave: IndependentWeave<u32, WeaveContent, u32, RandomState> =
+        IndependentWeave::with_capacity(8, 0);
+
+    for (id, from) in [
+        (0, vec![]),
+        (1, vec![0]),
+        (2, vec![0]),
+        (3, vec![2, 1]),
+        (4, vec![1, 2]),
+        (5, vec![0, 3]),
+        (6, vec![3]),
+        (7, vec![]),
+    ] {
+        assert!(weave.add_node(IndependentNode {
+            id,
+            from: IndexSet::from_iter(from),
+            to: IndexSet::default(),
+            active: false,
+            bookmarked: false,
+            contents: WeaveContent {
+                length: 1,
+                content_seed: 0,
+            },
+            style: None,
+        }));
+    }
+
+    assert_eq!(weave.lowest_common_ancestor(&3, &4), Some(1));
+    assert_eq!(weave.lowest_common_ancestor(&5, &6), Some(3));
+    assert_eq!(weave.lowest_common_ancestor(&3, &1), Some(1));
+    assert_eq!(weave.lowest_common_ancestor(&3, &7), None);
+    assert_eq!(weave.lowest_common_ancestor(&3, &8), None);
+}
+
+#[test]
+fn node_ancestry() {
+    let mut weave: IndependentWeave<u32, WeaveContent, u32, RandomState> =
+        IndependentWeave::with_capacity(5, 0);
+
+    for (id, from) in [
+        (0, vec![]),
+        (1, vec![0]),
+        (2, vec![0]),
+        (3, vec![1, 2]),
+        (4, vec![3]),
+    ] {
+        assert!(weave.add_node(IndependentNode {
+            id,
+            from: IndexSet::from_iter(from),
+            to: IndexSet::default(),
+            active: false,
+            bookmarked: false,
+            contents: WeaveContent {
+                length: 1,
+                content_seed: 0,
+            },
+            style: None,
+        }));
+    }
+
+    assert!(weave.is_ancestor_of(&0, &4));
+    assert!(weave.is_ancestor_of(&2, &4));
+    assert!(!weave.is_ancestor_of(&4, &2));
+    assert!(!weave.is_ancestor_of(&1, &2));
+    assert!(!weave.is_ancestor_of(&4, &4));
+
+    assert!(!weave.move_node(&1, &[4]));
+    assert!(!weave.move_node(&0, &[2, 3]));
+    assert!(weave.move_node(&1, &[2]));
+    assert!(weave.is_ancestor_of(&2, &1));
+}
+
+#[test]
+fn zero_capacity() {
+    let mut weave: IndependentWeave<u32, WeaveContent, u32, RandomState> =
+        IndependentWeave::with_capacity(0, 0);
+    assert_eq!(weave.capacity(), 0);
+
+    for id in 0..64 {
+        assert!(weave.add_node(IndependentNode {
+            id,
+            from: IndexSet::from_iter(id.checked_sub(1)),
+            to: IndexSet::default(),
+            active: id == 63,
+            bookmarked: false,
+            contents: WeaveContent {
+                length: 1,
+                content_seed: 0,
+            },
+            style: None,
+        }));
+    }
+
+    assert_eq!(weave.len(), 64);
+    assert!(weave.capacity() >= 64);
+
+    weave.reserve(64);
+    assert!(weave.capacity() >= 128);
+
+    assert_eq!(weave.try_reserve(128), Ok(()));
+    assert!(weave.capacity() >= 192);
+    assert_eq!(weave.len(), 64);
+}
+
+#[test]
+fn retain_nodes() {
+    let mut weave: IndependentWeave<u32, WeaveContent, u32, RandomState> =
+        IndependentWeave::with_capacity(5, 0);
+
+    for (id, from) in [
+        (0, vec![]),
+        (1, vec![0]),
+        (2, vec![0]),
+        (3, vec![1, 2]),
+        (4, vec![3]),
+    ] {
+        assert!(weave.add_node(IndependentNode {
+            id,
+            from: IndexSet::from_iter(from),
+            to: IndexSet::default(),
+            active: id == 4,
+            bookmarked: false,
+            contents: WeaveContent {
+                length: 1,
+                content_seed: 0,
+            },
+            style: None,
+        }));
+    }
+
+    weave.retain(|n| n.id != 1);
+    assert_eq!(weave.len(), 4);
+    assert!(weave.contains_active(&0));
+    assert!(!weave.contains_active(&3) && !weave.contains_active(&4));
+
+    assert!(weave.set_node_active_status(&4, true, false));
+    assert!([0, 2, 3, 4].iter().all(|id| weave.contains_active(id)));
+
+    weave.retain(|n| n.id != 2);
+    assert_eq!(weave.len(), 1);
+    assert!(weave.contains_active(&0));
+}
+
+#[test]
+fn rebuild_from_parts() {
+    let mut weave: IndependentWeave<u32, WeaveContent, u32, RandomState> =
+        IndependentWeave::with_capacity(5, 7);
+
+    for (id, from) in [
+        (0, vec![]),
+        (1, vec![0]),
+        (2, vec![0]),
+        (3, vec![1, 2]),
+        (4, vec![]),
+    ] {
+        assert!(weave.add_node(IndependentNode {
+            id,
+            from: IndexSet::from_iter(from),
+            to: IndexSet::default(),
+            active: id == 3,
+            bookmarked: id % 2 == 0,
+            contents: WeaveContent {
+                length: 1,
+                content_seed: id,
+            },
+            style: None,
+        }));
+    }
+
+    let parts = || {
+        (
+            weave.nodes().values().cloned().collect::<Vec<_>>(),
+            weave.roots().clone(),
+            weave.active().clone(),
+            weave.bookmarks().clone(),
+        )
+    };
+
+    let (nodes, roots, active, bookmarked) = parts();
+    let rebuilt =
+        IndependentWeave::rebuild_from_parts(nodes, roots, active, bookmarked, 7).unwrap();
+
+    assert_eq!(rebuilt.nodes(), weave.nodes());
+    assert_eq!(rebuilt.roots(), weave.roots());
+    assert_eq!(rebuilt.active(), weave.active());
+    assert_eq!(rebuilt.bookmarks(), weave.bookmarks());
+    assert_eq!(*rebuilt.metadata(), 7);
+
+    let (nodes, roots, mut active, bookmarked) = parts();
+    active.insert(4);
+    assert_eq!(
+        IndependentWeave::rebuild_from_parts(nodes, roots, active, bookmarked, 7).err(),
+        Some(WeaveError::InvalidActive)
+    );
+
+    let (mut nodes, roots, mut active, bookmarked) = parts();
+    for node in nodes.iter_mut().filter(|node| node.id == 4) {
+        node.active = true;
+    }
+    active.insert(4);
+    assert_eq!(
+        IndependentWeave::rebuild_from_parts(nodes, roots, active, bookmarked, 7).err(),
+        Some(WeaveError::InvalidActive)
+    );
+
+    let (mut nodes, roots, active, bookmarked) = parts();
+    nodes.push(nodes[0].clone());
+    assert_eq!(
+        IndependentWeave::rebuild_from_parts(nodes, roots, active, bookmarked, 7).err(),
+        Some(WeaveError::DuplicateNode)
+    );
+
+    let (mut nodes, roots, active, bookmarked) = parts();
+    for node in nodes.iter_mut().filter(|node| node.id == 4) {
+        node.to.insert(5);
+    }
+    assert_eq!(
+        IndependentWeave::rebuild_from_parts(nodes, roots, active, bookmarked, 7).err(),
+        Some(WeaveError::DanglingEdge)
+    );
+
+    let (nodes, mut roots, active, bookmarked) = parts();
+    roots.shift_remove(&4);
+    assert_eq!(
+        IndependentWeave::rebuild_from_parts(nodes, roots, active, bookmarked, 7).err(),
+        Some(WeaveError::InvalidRoots)
+    );
+}
+
+#[test]
+fn remove_deep_chain() {
+    const DEPTH: u32 = 100_000;
+
+    let nodes = (0..DEPTH)
+        .map(|id| IndependentNode {
+            id,
+            from: IndexSet::from_iter(id.checked_sub(1)),
+            to: IndexSet::from_iter((id + 1 < DEPTH).then_some(id + 1)),
+            active: false,
+            bookmarked: id == DEPTH - 1,
+            contents: WeaveContent {
+                length: 1,
+                content_seed: id,
+            },
+            style: None,
+        })
+        .collect::<Vec<_>>();
+
+    let mut weave: IndependentWeave<u32, WeaveContent, (), RandomState> =
+        IndependentWeave::rebuild_from_parts(
+            nodes,
+            IndexSet::from_iter([0]),
+            HashSet::default(),
+            IndexSet::from_iter([DEPTH - 1]),
+            (),
+        )
+        .unwrap();
+
+    assert!(weave.remove_node(&0).is_some());
+    assert!(weave.is_empty());
+    assert!(weave.roots().is_empty());
+    assert!(weave.bookmarks().is_empty());
+}
+
+#[test]
+fn scratchpads_cleared() {
+    let mut weave: IndependentWeave<u32, WeaveContent, (), RandomState> =
+        IndependentWeave::with_capacity(6, ());
+    let mut output = Vec::new();
+
+    for (id, from) in [
+        (0, vec![]),
+        (1, vec![0]),
+        (2, vec![0]),
+        (3, vec![1, 2]),
+        (4, vec![3]),
+        (5, vec![]),
+    ] {
+        assert!(weave.add_node(IndependentNode {
+            id,
+            from: IndexSet::from_iter(from),
+            to: IndexSet::default(),
+            active: id == 4,
+            bookmarked: false,
+            contents: WeaveContent {
+                length: 1,
+                content_seed: id,
+            },
+            style: None,
+        }));
+        assert!(weave.scratchpads_clean());
+    }
+
+    assert!(weave.set_node_active_status(&2, true, false));
+    assert!(weave.scratchpads_clean());
+    assert!(weave.set_node_active_status(&1, false, false));
+    assert!(weave.scratchpads_clean());
+    assert!(weave.move_node(&4, &[2]));
+    assert!(weave.scratchpads_clean());
+    assert!(!weave.move_node(&0, &[4]));
+    assert!(weave.scratchpads_clean());
+    assert!(!weave.move_all_children(&0, &3));
+    assert!(weave.scratchpads_clean());
+    assert!(weave.move_all_children(&3, &5));
+    assert!(weave.scratchpads_clean());
+
+    weave.get_active_thread(&mut output);
+    assert!(weave.scratchpads_clean());
+    weave.get_thread_from(&4, &mut output);
+    assert!(weave.scratchpads_clean());
+    weave.get_ordered_node_identifiers(&mut output);
+    assert!(weave.scratchpads_clean());
+    weave.get_ordered_node_identifiers_from(&2, &mut output);
+    assert!(weave.scratchpads_clean());
+
+    assert!(weave.remove_node(&0).is_some());
+    assert!(weave.scratchpads_clean());
+}
+
+#[test]
+fn deep_active_thread() {
+    const DEPTH: u32 = 100_000;
+
+    let nodes = (0..DEPTH)
+        .map(|id| IndependentNode {
+            id,
+            from: IndexSet::from_iter(id.checked_sub(1)),
+            to: IndexSet::from_iter((id + 1 < DEPTH).then_some(id + 1)),
+            active: false,
+            bookmarked: false,
+            contents: WeaveContent {
+                length: 1,
+                content_seed: id,
+            },
+            style: None,
+        })
+        .collect::<Vec<_>>();
+
+    let mut weave: IndependentWeave<u32, WeaveContent, (), RandomState> =
+        IndependentWeave::rebuild_from_parts(
+            nodes,
+            IndexSet::from_iter([0]),
+            HashSet::default(),
+            IndexSet::default(),
+            (),
+        )
+        .unwrap();
+
+    let mut thread = Vec::new();
+    weave.get_thread_from(&(DEPTH - 1), &mut thread);
+    assert!(thread.iter().copied().eq((0..DEPTH).rev()));
+
+    assert!(weave.set_node_active_status_in_place(&(DEPTH - 1), true));
+
+    weave.get_active_thread(&mut thread);
+    assert!(thread.iter().copied().eq((0..DEPTH).rev()));
+
+    let mut visited = Vec::new();
+    weave.for_each_active(|node| visited.push(node.id));
+    assert_eq!(visited, thread);
+
+    weave.get_thread_from(&(DEPTH / 2), &mut thread);
+    assert!(thread.iter().copied().eq((0..=DEPTH / 2).rev()));
+}
+
+#[test]
+fn for_each_active() {
+    let mut weave: IndependentWeave<u32, WeaveContent, (), RandomState> =
+        IndependentWeave::with_capacity(6, ());
+
+    for (id, from) in [
+        (0, vec![]),
+        (1, vec![0]),
+        (2, vec![0, 1]),
+        (3, vec![2]),
+        (4, vec![1]),
+        (5, vec![]),
+    ] {
+        assert!(weave.add_node(IndependentNode {
+            id,
+            from: IndexSet::from_iter(from),
+            to: IndexSet::default(),
+            active: false,
+            bookmarked: false,
+            contents: WeaveContent {
+                length: 1,
+                content_seed: id,
+            },
+            style: None,
+        }));
+    }
+
+    let mut visited = Vec::new();
+    weave.for_each_active(|node| visited.push(node.id));
+    assert!(visited.is_empty());
+
+    for id in [1, 2, 3] {
+        assert!(weave.set_node_active_status_in_place(&id, true));
+    }
+
+    let mut thread = Vec::new();
+    weave.get_active_thread(&mut thread);
+    weave.for_each_active(|node| visited.push(node.id));
+    assert_eq!(visited, thread);
+    assert_eq!(visited, vec![3, 2, 1, 0]);
+}
+
+#[test]
+fn sibling_navigation() {
+    let mut weave: IndependentWeave<u32, WeaveContent, (), RandomState> =
+        IndependentWeave::with_capacity(6, ());
+
+    for (id, from) in [
+        (0, vec![]),
+        (1, vec![]),
+        (2, vec![0]),
+        (3, vec![1, 0]),
+        (4, vec![0]),
+        (5, vec![1]),
+    ] {
+        assert!(weave.add_node(IndependentNode {
+            id,
+            from: IndexSet::from_iter(from),
+            to: IndexSet::default(),
+            active: false,
+            bookmarked: false,
+            contents: WeaveContent {
+                length: 1,
+                content_seed: id,
+            },
+            style: None,
+        }));
+    }
+
+    assert_eq!(weave.next_sibling(&2), Some(3));
+    assert_eq!(weave.next_sibling(&3), Some(5));
+    assert_eq!(weave.prev_sibling(&3), None);
+    assert_eq!(weave.prev_sibling(&4), Some(3));
+    assert_eq!(weave.next_sibling(&4), None);
+
+    assert_eq!(weave.next_sibling(&0), Some(1));
+    assert_eq!(weave.prev_sibling(&0), None);
+
+    assert_eq!(weave.next_sibling(&6), None);
+    assert_eq!(weave.prev_sibling(&6), None);
+}
+
+#[test]
+fn sibling_index() {
+    let mut weave: IndependentWeave<u32, WeaveContent, (), RandomState> =
+        IndependentWeave::with_capacity(5, ());
+
+    for (id, from) in [
+        (0, vec![]),
+        (1, vec![]),
+        (2, vec![0]),
+        (3, vec![1, 0]),
+        (4, vec![0]),
+    ] {
+        assert!(weave.add_node(IndependentNode {
+            id,
+            from: IndexSet::from_iter(from),
+            to: IndexSet::default(),
+            active: false,
+            bookmarked: false,
+            contents: WeaveContent {
+                length: 1,
+                content_seed: id,
+            },
+            style: None,
+        }));
+    }
+
+    assert_eq!(weave.get_sibling_index(&2), Some((0, 3)));
+    assert_eq!(weave.get_sibling_index(&3), Some((0, 1)));
+    assert_eq!(weave.get_sibling_index(&4), Some((2, 3)));
+    assert_eq!(weave.get_sibling_index(&1), Some((1, 2)));
+    assert_eq!(weave.get_sibling_index(&5), None);
+}
+
+#[test]
+fn node_counts() {
+    let mut weave: IndependentWeave<u32, WeaveContent, u32, RandomState> =
+        IndependentWeave::with_capacity(4, 0);
+
+    for (id, from) in [(0, vec![]), (1, vec![0]), (2, vec![0]), (3, vec![2])] {
+        assert!(weave.add_node(IndependentNode {
+            id,
+            from: IndexSet::from_iter(from),
+            to: IndexSet::default(),
+            active: id == 3,
+            bookmarked: id != 2,
+            contents: WeaveContent {
+                length: 1,
+                content_seed: id,
+            },
+            style: None,
+        }));
+    }
+
+    assert_eq!(weave.count_active(), 3);
+    assert_eq!(weave.count_bookmarked(), 3);
+
+    #[cfg(feature = "rkyv")]
+    {
+        use universal_weave::{ArchivedWeave, independent::ArchivedIndependentWeave};
+
+        let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&weave).unwrap();
+        let archived = rkyv::access::<
+            ArchivedIndependentWeave<u32, WeaveContent, u32, RandomState>,
+            rkyv::rancor::Error,
+        >(&bytes)
+        .unwrap();
+
+        assert_eq!(archived.count_bookmarked(), 3);
+    }
+
+    assert!(weave.set_node_active_status(&3, false, false));
+    assert!(weave.set_node_bookmarked_status(&0, false));
+    assert_eq!(weave.count_active(), 2);
+    assert_eq!(weave.count_bookmarked(), 2);
+}
+
+#[test]
+fn all_threads() {
+    let mut weave: IndependentWeave<u32, WeaveContent, (), RandomState> =
+        IndependentWeave::with_capacity(6, ());
+
+    for (id, from) in [
+        (0, vec![]),
+        (1, vec![0]),
+        (2, vec![0]),
+        (3, vec![1, 2]),
+        (4, vec![3]),
+        (5, vec![]),
+    ] {
+        assert!(weave.add_node(IndependentNode {
+            id,
+            from: IndexSet::from_iter(from),
+            to: IndexSet::default(),
+            active: false,
+            bookmarked: false,
+            contents: WeaveContent {
+                length: 1,
+                content_seed: id,
+            },
+            style: None,
+        }));
+    }
+
+    assert_eq!(
+        weave.get_all_threads().collect::<Vec<_>>(),
+        vec![vec![4, 3, 1, 0], vec![4, 3, 2, 0], vec![5]]
+    );
+
+    let mut threads = weave.get_all_threads();
+    assert_eq!(threads.next(), Some(vec![4, 3, 1, 0]));
+    drop(threads);
+
+    assert!(
+        IndependentWeave::<u32, WeaveContent, (), RandomState>::with_capacity(0, ())
+            .get_all_threads()
+            .next()
+            .is_none()
+    );
+}
+
+#[test]
+fn shortest_path() {
+    let mut weave: IndependentWeave<u32, WeaveContent, (), RandomState> =
+        IndependentWeave::with_capacity(7, ());
+
+    for (id, from) in [
+        (0, vec![]),
+        (1, vec![0]),
+        (2, vec![0]),
+        (3, vec![2]),
+        (4, vec![1, 3]),
+        (5, vec![4]),
+        (6, vec![]),
+    ] {
+        assert!(weave.add_node(IndependentNode {
+            id,
+            from: IndexSet::from_iter(from),
+            to: IndexSet::default(),
+            active: false,
+            bookmarked: false,
+            contents: WeaveContent {
+                length: 1,
+                content_seed: id,
+            },
+            style: None,
+        }));
+    }
+
+    assert_eq!(weave.shortest_path(&0, &5), Some(vec![0, 1, 4, 5]));
+    assert_eq!(weave.shortest_path(&5, &0), Some(vec![5, 4, 1, 0]));
+    assert_eq!(weave.shortest_path(&3, &1), Some(vec![3, 4, 1]));
+    assert_eq!(weave.shortest_path(&2, &2), Some(vec![2]));
+    assert_eq!(weave.shortest_path(&0, &6), None);
+    assert_eq!(weave.shortest_path(&0, &7), None);
+    assert!(weave.scratchpads_clean());
+}
+
+#[test]
+fn subtree_size() {
+    let mut weave: IndependentWeave<u32, WeaveContent, u32, RandomState> =
+        IndependentWeave::with_capacity(6, 0);
+
+    for (id, from) in [
+        (0, vec![]),
+        (1, vec![0]),
+        (2, vec![0]),
+        (3, vec![1, 2]),
+        (4, vec![3]),
+        (5, vec![]),
+    ] {
+        assert!(weave.add_node(IndependentNode {
+            id,
+            from: IndexSet::from_iter(from),
+            to: IndexSet::default(),
+            active: false,
+            bookmarked: false,
+            contents: WeaveContent {
+                length: 1,
+                content_seed: id,
+            },
+            style: None,
+        }));
+    }
+
+    assert_eq!(weave.subtree_size(&0), Some(5));
+    assert_eq!(weave.subtree_size(&1), Some(3));
+    assert_eq!(weave.subtree_size(&3), Some(2));
+    assert_eq!(weave.subtree_size(&4), Some(1));
+    assert_eq!(weave.subtree_size(&5), Some(1));
+    assert_eq!(weave.subtree_size(&6), None);
+
+    #[cfg(feature = "rkyv")]
+    {
+        use universal_weave::{ArchivedWeave, independent::ArchivedIndependentWeave};
+
+        let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&weave).unwrap();
+        let archived = rkyv::access::<
+            ArchivedIndependentWeave<u32, WeaveContent, u32, RandomState>,
+            rkyv::rancor::Error,
+        >(&bytes)
+        .unwrap();
+
+        assert_eq!(archived.subtree_size(&0.into()), Some(5));
+        assert_eq!(archived.subtree_size(&1.into()), Some(3));
+        assert_eq!(archived.subtree_size(&6.into()), None);
+    }
+}
+
+#[test]
+fn node_builder() {
+    let mut weave: IndependentWeave<u32, WeaveContent, (), RandomState> =
+        IndependentWeave::with_capacity(3, ());
+
+    let contents = |id| WeaveContent {
+        length: 1,
+        content_seed: id,
+    };
+
+    assert!(weave.add_node(NodeBuilder::new(0, contents(0)).build()));
+    assert!(weave.add_node(NodeBuilder::new(1, contents(1)).bookmarked(true).build()));
+    assert!(
+        weave.add_node(
+            NodeBuilder::new(2, contents(2))
+                .parents([0, 1])
+                .active(true)
+                .build()
+        )
+    );
+
+    assert_eq!(
+        weave.get_node(&2),
+        Some(&IndependentNode {
+            id: 2,
+            from: IndexSet::from_iter([0, 1]),
+            to: IndexSet::default(),
+            active: true,
+            bookmarked: false,
+            contents: contents(2),
+            style: None,
+        })
+    );
+    assert!(weave.contains_bookmark(&1));
+    assert!(!weave.contains_bookmark(&0));
+}
+
+#[test]
+fn split_node_multi() {
+    let mut weave: IndependentWeave<u32, WeaveContent, (), RandomState> =
+        IndependentWeave::with_capacity(6, ());
+
+    for (id, length, from) in [(0, 10, vec![]), (1, 1, vec![0]), (2, 1, vec![0])] {
+        assert!(weave.add_node(IndependentNode {
+            id,
+            from: IndexSet::from_iter(from),
+            to: IndexSet::default(),
+            active: false,
+            bookmarked: id == 0,
+            contents: WeaveContent {
+                length,
+                content_seed: 0,
+            },
+            style: None,
+        }));
+    }
+    assert!(weave.set_node_active_status(&1, true, false));
+
+    assert!(!weave.split_node_multi(&0, &[2, 5], &[3]));
+    assert!(!weave.split_node_multi(&0, &[5, 2], &[3, 4]));
+    assert!(!weave.split_node_multi(&0, &[2, 2], &[3, 4]));
+    assert!(!weave.split_node_multi(&0, &[2, 5], &[3, 3]));
+    assert!(!weave.split_node_multi(&0, &[2, 5], &[3, 1]));
+    assert!(!weave.split_node_multi(&0, &[2, 10], &[3, 4]));
+    assert!(!weave.split_node_multi(&5, &[2, 5], &[3, 4]));
+    assert_eq!(weave.len(), 3);
+    assert_eq!(weave.get_node(&0).unwrap().contents.length, 10);
+    assert_eq!(
+        weave.get_node(&0).unwrap().to,
+        IndexSet::<u32, RandomState>::from_iter([1, 2])
+    );
+
+    assert!(weave.split_node_multi(&0, &[2, 5], &[3, 4]));
+    assert_eq!(weave.len(), 5);
+
+    let chain: Vec<_> = [0, 3, 4]
+        .iter()
+        .map(|id| {
+            let node = weave.get_node(id).unwrap();
+            (
+                node.contents.length,
+                node.from.iter().copied().collect::<Vec<_>>(),
+                node.to.iter().copied().collect::<Vec<_>>(),
+                node.active,
+                node.bookmarked,
+            )
+        })
+        .collect();
+    assert_eq!(
+        chain,
+        vec![
+            (2, vec![], vec![3], true, true),
+            (3, vec![0], vec![4], true, false),
+            (5, vec![3], vec![1, 2], true, false),
+        ]
+    );
+    assert!(weave.get_node(&1).unwrap().from.iter().eq([&4]));
+    assert!(weave.get_node(&2).unwrap().from.iter().eq([&4]));
+
+    let mut thread = Vec::new();
+    weave.get_active_thread(&mut thread);
+    assert_eq!(thread, vec![1, 4, 3, 0]);
+}
+
+#[test]
+fn merge_chain() {
+    let mut weave: IndependentWeave<u32, WeaveContent, (), RandomState> =
+        IndependentWeave::with_capacity(7, ());
+
+    for (id, content_seed, from) in [
+        (0, 1, vec![]),
+        (1, 0, vec![0]),
+        (2, 0, vec![1]),
+        (3, 0, vec![2]),
+        (4, 0, vec![3]),
+        (5, 0, vec![3]),
+        (6, 0, vec![]),
+    ] {
+        assert!(weave.add_node(IndependentNode {
+            id,
+            from: IndexSet::from_iter(from),
+            to: IndexSet::default(),
+            active: false,
+            bookmarked: false,
+            contents: WeaveContent {
+                length: 1,
+                content_seed,
+            },
+            style: None,
+        }));
+    }
+    assert!(weave.set_node_active_status(&4, true, false));
+
+    assert_eq!(weave.merge_chain(&2), Some(1));
+    assert_eq!(weave.len(), 5);
+    assert_eq!(weave.get_node(&1).unwrap().contents.length, 3);
+    assert!(weave.get_node(&1).unwrap().from.iter().eq([&0]));
+    assert!(weave.get_node(&1).unwrap().to.iter().eq([&4, &5]));
+
+    let mut thread = Vec::new();
+    weave.get_active_thread(&mut thread);
+    assert_eq!(thread, vec![4, 1, 0]);
+
+    assert_eq!(weave.merge_chain(&1), None);
+    assert_eq!(weave.merge_chain(&6), None);
+    assert_eq!(weave.merge_chain(&7), None);
+    assert_eq!(weave.len(), 5);
+}
+
+#[test]
+fn deduplicate_all() {
+    let mut weave: IndependentWeave<u32, WeaveContent, (), RandomState> =
+        IndependentWeave::with_capacity(9, ());
+
+    for (id, content_seed, from) in [
+        (0, 0, vec![]),
+        (1, 1, vec![0]),
+        (2, 1, vec![0]),
+        (3, 2, vec![1]),
+        (4, 3, vec![2]),
+        (5, 2, vec![2]),
+        (6, 0, vec![]),
+        (7, 4, vec![6]),
+        (8, 4, vec![5]),
+    ] {
+        assert!(weave.add_node(IndependentNode {
+            id,
+            from: IndexSet::from_iter(from),
+            to: IndexSet::default(),
+            active: false,
+            bookmarked: id == 2,
+            contents: WeaveContent {
+                length: 1,
+                content_seed,
+            },
+            style: None,
+        }));
+    }
+    assert!(weave.set_node_active_status(&8, true, false));
+
+    assert_eq!(weave.deduplicate_all(), 3);
+    assert_eq!(weave.len(), 6);
+    assert!(weave.roots().iter().eq([&0]));
+    assert!(weave.get_node(&0).unwrap().to.iter().eq([&1, &7]));
+    assert!(weave.get_node(&1).unwrap().to.iter().eq([&3, &4]));
+    assert!(weave.get_node(&3).unwrap().to.iter().eq([&8]));
+    assert!(weave.get_node(&7).unwrap().from.iter().eq([&0]));
+    assert!(weave.contains_bookmark(&1));
+    assert!(!weave.contains(&2) && !weave.contains(&5) && !weave.contains(&6));
+
+    let mut thread = Vec::new();
+    weave.get_active_thread(&mut thread);
+    assert_eq!(thread, vec![8, 3, 1, 0]);
+
+    assert_eq!(weave.deduplicate_all(), 0);
+}
+
+#[test]
+fn find_duplicate_groups() {
+    let mut weave: IndependentWeave<u32, WeaveContent, (), RandomState> =
+        IndependentWeave::with_capacity(7, ());
+
+    for (id, content_seed, from) in [
+        (0, 0, vec![]),
+        (1, 1, vec![0]),
+        (2, 2, vec![1]),
+        (3, 1, vec![2]),
+        (4, 3, vec![]),
+        (5, 2, vec![4]),
+        (6, 1, vec![5]),
+    ] {
+        assert!(weave.add_node(IndependentNode {
+            id,
+            from: IndexSet::from_iter(from),
+            to: IndexSet::default(),
+            active: false,
+            bookmarked: false,
+            contents: WeaveContent {
+                length: 1,
+                content_seed,
+            },
+            style: None,
+        }));
+    }
+
+    let mut groups: Vec<Vec<u32>> = weave
+        .find_duplicate_groups()
+        .map(|mut group| {
+            group.sort();
+            group
+        })
+        .collect();
+    groups.sort();
+
+    assert_eq!(groups, vec![vec![1, 3, 6], vec![2, 5]]);
+}
+
+#[derive(Default)]
+struct KeyHasher(u64);
+
+impl Hasher for KeyHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+    fn write(&mut self, _bytes: &[u8]) {
+        panic!("KeyHasher only supports hashing u32 keys");
+    }
+    fn write_u32(&mut self, value: u32) {
+        self.0 = value.into();
+    }
+}
+
+#[test]
+fn find_duplicate_groups_with_key_hasher() {
+    let mut weave: IndependentWeave<u32, WeaveContent, (), BuildHasherDefault<KeyHasher>> =
+        IndependentWeave::with_capacity(3, ());
+
+    for (id, from) in [(0, vec![]), (1, vec![0]), (2, vec![])] {
+        assert!(weave.add_node(IndependentNode {
+            id,
+            from: IndexSet::from_iter(from),
+            to: IndexSet::default(),
+            active: false,
+            bookmarked: false,
+            contents: WeaveContent {
+                length: 1,
+                content_seed: 0,
+            },
+            style: None,
+        }));
+    }
+
+    let mut groups: Vec<Vec<u32>> = weave.find_duplicate_groups().collect();
+    groups[0].sort();
+
+    assert_eq!(groups, vec![vec![0, 1, 2]]);
+}
+
+#[cfg(feature = "rkyv")]
+#[test]
+fn archived_to_owned() {
+    use universal_weave::independent::ArchivedIndependentWeave;
+
+    let mut weave: IndependentWeave<u32, WeaveContent, u32, RandomState> =
+        IndependentWeave::with_capacity(4, 7);
+
+    for (id, from) in [(0, vec![]), (1, vec![0]), (2, vec![0]), (3, vec![1, 2])] {
+        assert!(weave.add_node(IndependentNode {
+            id,
+            from: IndexSet::from_iter(from),
+            to: IndexSet::default(),
+            active: id == 3,
+            bookmarked: id == 1,
+            contents: WeaveContent {
+                length: 1,
+                content_seed: id,
+            },
+            style: None,
+        }));
+    }
+
+    let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&weave).unwrap();
+    let archived = rkyv::access::<
+        ArchivedIndependentWeave<u32, WeaveContent, u32, RandomState>,
+        rkyv::rancor::Error,
+    >(&bytes)
+    .unwrap();
+    let mut owned = archived.to_owned_weave().unwrap();
+    drop(bytes);
+
+    assert_eq!(owned.nodes(), weave.nodes());
+    assert_eq!(owned.roots(), weave.roots());
+    assert_eq!(owned.bookmarks(), weave.bookmarks());
+    assert_eq!(owned.active(), weave.active());
+    assert_eq!(owned.metadata(), &7);
+    assert!(owned.scratchpads_clean());
+
+    let mut thread = Vec::new();
+    owned.get_active_thread(&mut thread);
+    assert_eq!(thread.first(), Some(&3));
+}
+
+#[test]
+fn observed_weave() {
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let mut weave: ObservedWeave<IndependentWeave<u32, WeaveContent, (), RandomState>, _, _, _> =
+        IndependentWeave::with_capacity(3, ()).into();
+
+    let observer_events = events.clone();
+    weave.set_observer(Box::new(move |event| {
+        observer_events.borrow_mut().push(event)
+    }));
+
+    for (id, from) in [(0, vec![]), (1, vec![0]), (2, vec![0])] {
+        assert!(weave.add_node(IndependentNode {
+            id,
+            from: IndexSet::from_iter(from),
+            to: IndexSet::default(),
+            active: id == 2,
+            bookmarked: id == 2,
+            contents: WeaveContent {
+                length: 1,
+                content_seed: 0,
+            },
+            style: None,
+        }));
+    }
+    assert!(!weave.set_node_bookmarked_status(&3, true));
+    assert!(weave.sort_node_children_by_id(&0, |a, b| b.cmp(a)));
+    assert!(weave.set_node_bookmarked_status(&1, true));
+    weave.sort_roots_by_id(|a, b| a.cmp(b));
+
+    assert_eq!(
+        events.borrow_mut().drain(..).collect::<Vec<_>>(),
+        [
+            WeaveEvent::NodeAdded(0),
+            WeaveEvent::NodeAdded(1),
+            WeaveEvent::NodeAdded(2),
+            WeaveEvent::ActiveChanged,
+            WeaveEvent::BookmarkChanged(2),
+            WeaveEvent::ChildrenReordered(0),
+            WeaveEvent::BookmarkChanged(1),
+            WeaveEvent::RootsReordered,
+        ]
+    );
+
+    assert!(weave.with_node_mut(&1, |node| node.bookmarked = false));
+    assert!(weave.with_node_mut(&1, |node| node.contents.content_seed = 1));
+    assert!(!weave.with_node_mut(&3, |node| node.contents.content_seed = 1));
+    assert_eq!(
+        events.borrow_mut().drain(..).collect::<Vec<_>>(),
+        [
+            WeaveEvent::BookmarkChanged(1),
+            WeaveEvent::ContentsChanged(1)
+        ]
+    );
+
+    assert!(weave.remove_node(&2).is_some());
+    assert_eq!(
+        events.borrow_mut().drain(..).collect::<Vec<_>>(),
+        [WeaveEvent::NodeRemoved(2), WeaveEvent::ActiveChanged]
+    );
+
+    assert!(weave.remove_node(&0).is_some());
+    assert!(weave.is_empty());
+    assert_eq!(
+        events.borrow_mut().drain(..).collect::<Vec<_>>(),
+        [
+            WeaveEvent::NodeRemoved(1),
+            WeaveEvent::NodeRemoved(0),
+            WeaveEvent::ActiveChanged
+        ]
+    );
+
+    weave.take_observer();
+    assert!(weave.add_node(IndependentNode {
+        id: 0,
+        from: IndexSet::default(),
+        to: IndexSet::default(),
+        active: false,
+        bookmarked: false,
+        contents: WeaveContent {
+            length: 1,
+            content_seed: 0,
+        },
+        style: None,
+    }));
+    assert!(events.borrow().is_empty());
+}
+
+#[test]
+fn diff() {
+    let mut old: IndependentWeave<u32, WeaveContent, u32, RandomState> =
+        IndependentWeave::with_capacity(6, 0);
+    let mut new = IndependentWeave::with_capacity(6, 1);
+
+    for (id, from, content_seed, bookmarked) in [
+        (0, vec![], 0, false),
+        (1, vec![0], 1, true),
+        (2, vec![0], 2, false),
+        (3, vec![1], 3, false),
+        (4, vec![2], 4, true),
+    ] {
+        assert!(old.add_node(IndependentNode {
+            id,
+            from: IndexSet::from_iter(from),
+            to: IndexSet::default(),
+            active: false,
+            bookmarked,
+            contents: WeaveContent {
+                length: 1,
+                content_seed,
+            },
+            style: None,
+        }));
+    }
+    assert!(old.set_node_active_status(&3, true, false));
+
+    for (id, from, content_seed, bookmarked) in [
+        (0, vec![], 0, false),
+        (5, vec![], 5, true),
+        (2, vec![0, 5], 2, true),
+        (1, vec![2], 6, false),
+        (3, vec![1], 3, false),
+        (6, vec![2], 7, true),
+    ] {
+        assert!(new.add_node(IndependentNode {
+            id,
+            from: IndexSet::from_iter(from),
+            to: IndexSet::default(),
+            active: false,
+            bookmarked,
+            contents: WeaveContent {
+                length: 1,
+                content_seed,
+            },
+            style: None,
+        }));
+    }
+    assert!(new.sort_node_children_by_id(&2, |a, b| b.cmp(a)));
+    assert!(new.set_node_active_status(&6, true, false));
+
+    let actions = old.diff(&new);
+    assert!(!actions.is_empty());
+
+    for action in actions {
+        old.apply(action);
+    }
+
+    assert_same_weave(&old, &new);
+    assert!(old.diff(&new).is_empty());
+}
+
+#[test]
+fn diff_excludes_style_changes() {
+    let style = NodeStyle {
+        color: [255, 0, 0, 255],
+        collapsed: true,
+    };
+    let node = |id: u32, from: Vec<u32>, style: Option<NodeStyle>| IndependentNode {
+        id,
+        from: IndexSet::from_iter(from),
+        to: IndexSet::default(),
+        active: false,
+        bookmarked: false,
+        contents: WeaveContent {
+            length: 1,
+            content_seed: id,
+        },
+        style,
+    };
+
+    let mut old: IndependentWeave<u32, WeaveContent, u32, RandomState> =
+        IndependentWeave::with_capacity(2, 0);
+    assert!(old.add_node(node(0, vec![], None)));
+
+    let mut new = old.clone();
+    assert!(new.set_node_style(&0, Some(style)));
+    assert!(new.add_node(node(1, vec![0], Some(style))));
+
+    for action in old.diff(&new) {
+        old.apply(action);
+    }
+
+    assert_eq!(old.get_node_style(&0), None);
+    assert_eq!(old.get_node_style(&1), Some(style));
+    assert!(old.diff(&new).is_empty());
+}
+
+#[test]
+fn apply_actions() {
+    let node = |id: u32, from: Vec<u32>| IndependentNode {
+        id,
+        from: IndexSet::from_iter(from),
+        to: IndexSet::default(),
+        active: false,
+        bookmarked: false,
+        contents: WeaveContent {
+            length: 1,
+            content_seed: id,
+        },
+        style: None,
+    };
+    let mut weave: IndependentWeave<u32, WeaveContent, u32, RandomState> =
+        IndependentWeave::with_capacity(5, 0);
+    assert!(weave.add_node(node(0, vec![])));
+    assert!(weave.add_node(node(1, vec![0])));
+
+    let actions = [
+        WeaveAction::AddNode(node(2, vec![1])),
+        WeaveAction::RemoveNode(3),
+        WeaveAction::SetNodeBookmarkedStatus { id: 2, value: true },
+        WeaveAction::SetNodeActiveStatusInPlace { id: 2, value: true },
+    ];
+    assert!(weave.apply_actions(&actions));
+    assert!(weave.apply_actions(&actions));
+    assert_eq!(weave.len(), 3);
+    assert!(weave.contains_bookmark(&2));
+    assert_eq!(weave.active_thread_vec(), [2, 1, 0]);
+
+    assert!(weave.apply_actions(&[WeaveAction::RemoveNode(2)]));
+    assert!(weave.apply_actions(&[WeaveAction::RemoveNode(2)]));
+    assert!(!weave.contains(&2));
+
+    assert!(!weave.apply_actions(&[WeaveAction::SetNodeContent {
+        id: 9,
+        contents: WeaveContent {
+            length: 1,
+            content_seed: 9,
+        },
+    }]));
+    assert!(!weave.apply_actions(&[
+        WeaveAction::AddNode(node(3, vec![0])),
+        WeaveAction::MoveNode {
+            id: 9,
+            new_parents: vec![],
+        },
+        WeaveAction::AddNode(node(4, vec![0])),
+    ]));
+    assert!(weave.contains(&3));
+    assert!(!weave.contains(&4));
+    assert!(!weave.apply_actions(&[WeaveAction::SetNodeChildOrdering {
+        parent: Some(0),
+        children: vec![3],
+    }]));
+
+    let contents = WeaveContent {
+        length: 2,
+        content_seed: 3,
+    };
+    assert!(weave.apply_actions(&[WeaveAction::SetNodeContent {
+        id: 3,
+        contents: contents.clone(),
+    }]));
+    assert_eq!(
+        weave.get_node(&3).map(|node| &node.contents),
+        Some(&contents)
+    );
+
+    let split = [WeaveAction::SplitNode {
+        id: 3,
+        at: 1,
+        new_id: 5,
+    }];
+    assert!(!weave.apply_actions(&split));
+    assert!(!weave.contains(&5));
+    assert!(weave.apply_discrete_actions(&split));
+    assert!(weave.contains(&5));
+
+    let merge = [WeaveAction::MergeNodeWithParent(5)];
+    assert!(!weave.apply_actions(&merge));
+    assert!(weave.apply_discrete_actions(&merge));
+    assert!(!weave.contains(&5));
+    assert_eq!(
+        weave.get_node(&3).map(|node| &node.contents),
+        Some(&contents)
+    );
+}
+
+#[test]
+fn search_contents() {
+    let mut weave: IndependentWeave<u32, WeaveContent, u32, RandomState> =
+        IndependentWeave::with_capacity(5, 0);
+
+    for (id, from, content_seed) in [
+        (0, vec![], 1),
+        (1, vec![0], 2),
+        (2, vec![0], 1),
+        (3, vec![1, 2], 1),
+        (4, vec![], 3),
+    ] {
+        assert!(weave.add_node(IndependentNode {
+            id,
+            from: IndexSet::from_iter(from),
+            to: IndexSet::default(),
+            active: false,
+            bookmarked: false,
+            contents: WeaveContent {
+                length: 1,
+                content_seed,
+            },
+            style: None,
+        }));
+    }
+
+    let mut found: Vec<u32> = weave
+        .search_contents(|contents| contents.content_seed == 1)
+        .collect();
+    found.sort();
+    assert_eq!(found, [0, 2, 3]);
+    assert_eq!(weave.search_contents(|_| false).count(), 0);
+
+    #[cfg(feature = "rkyv")]
+    {
+        use universal_weave::{ArchivedWeave, independent::ArchivedIndependentWeave};
+
+        let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&weave).unwrap();
+        let archived = rkyv::access::<
+            ArchivedIndependentWeave<u32, WeaveContent, u32, RandomState>,
+            rkyv::rancor::Error,
+        >(&bytes)
+        .unwrap();
+
+        let mut found: Vec<u32> = archived
+            .search_contents(|contents| contents.content_seed == 1)
+            .map(|id| id.to_native())
+            .collect();
+        found.sort();
+        assert_eq!(found, [0, 2, 3]);
+    }
+}
+
+#[test]
+fn from_nodes() {
+    let node = |id: u32, from: Vec<u32>| IndependentNode {
+        id,
+        from: IndexSet::from_iter(from),
+        to: IndexSet::default(),
+        active: false,
+        bookmarked: id == 3,
+        contents: WeaveContent {
+            length: 1,
+            content_seed: id,
+        },
+        style: None,
+    };
+    let mut weave: IndependentWeave<u32, WeaveContent, u32, RandomState> =
+        IndependentWeave::with_capacity(6, 7);
+
+    for (id, from) in [
+        (0, vec![]),
+        (1, vec![0]),
+        (2, vec![0]),
+        (3, vec![1, 2]),
+        (4, vec![3]),
+        (5, vec![]),
+    ] {
+        assert!(weave.add_node(node(id, from)));
+    }
+    assert!(weave.sort_node_children_by_id(&0, |a, b| b.cmp(a)));
+    assert!(weave.set_node_active_status(&4, true, false));
+
+    let mut identifiers = Vec::new();
+    weave.get_ordered_node_identifiers(&mut identifiers);
+    let rebuilt = IndependentWeave::from_nodes(
+        identifiers
+            .iter()
+            .map(|id| weave.get_node(id).unwrap().clone()),
+        7,
+    )
+    .unwrap();
+    assert_same_weave(&rebuilt, &weave);
+
+    let rebuilt: IndependentWeave<u32, WeaveContent, u32, RandomState> =
+        IndependentWeave::from_nodes(
+            [
+                node(4, vec![3]),
+                node(3, vec![2, 1]),
+                node(0, vec![]),
+                node(2, vec![0]),
+                node(1, vec![0]),
+            ],
+            0,
+        )
+        .unwrap();
+    assert!(rebuilt.roots().iter().eq([&0]));
+    assert!(rebuilt.get_node(&0).unwrap().to.iter().eq([&2, &1]));
+    assert!(rebuilt.get_node(&3).unwrap().to.iter().eq([&4]));
+    assert!(rebuilt.contains_bookmark(&3));
+
+    assert!(
+        IndependentWeave::<u32, WeaveContent, u32, RandomState>::from_nodes(
+            [node(0, vec![]), node(0, vec![])],
+            0
+        )
+        .is_none()
+    );
+    assert!(
+        IndependentWeave::<u32, WeaveContent, u32, RandomState>::from_nodes(
+            [node(0, vec![]), node(1, vec![2])],
+            0
+        )
+        .is_none()
+    );
+    assert!(
+        IndependentWeave::<u32, WeaveContent, u32, RandomState>::from_nodes(
+            [node(0, vec![]), node(1, vec![0, 2]), node(2, vec![1])],
+            0
+        )
+        .is_none()
+    );
+    let mut orphaned_active = node(1, vec![0]);
+    orphaned_active.active = true;
+    assert!(
+        IndependentWeave::<u32, WeaveContent, u32, RandomState>::from_nodes(
+            [node(0, vec![]), orphaned_active],
+            0
+        )
+        .is_none()
+    );
+}
+
+#[test]
+fn graft() {
+    let node = |id: u32, from: Vec<u32>, active: bool| IndependentNode {
+        id,
+        from: IndexSet::from_iter(from),
+        to: IndexSet::default(),
+        active,
+        bookmarked: id % 2 == 1,
+        contents: WeaveContent {
+            length: 1,
+            content_seed: id,
+        },
+        style: None,
+    };
+    let mut weave: IndependentWeave<u32, WeaveContent, u32, RandomState> =
+        IndependentWeave::from_nodes([node(0, vec![], true), node(1, vec![0], true)], 0).unwrap();
+    let other: IndependentWeave<u32, WeaveContent, u32, RandomState> =
+        IndependentWeave::from_nodes(
+            [
+                node(10, vec![], false),
+                node(11, vec![], true),
+                node(12, vec![10, 11], true),
+            ],
+            1,
+        )
+        .unwrap();
+
+    let colliding: IndependentWeave<u32, WeaveContent, u32, RandomState> =
+        IndependentWeave::from_nodes([node(20, vec![], false), node(1, vec![20], false)], 0)
+            .unwrap();
+    assert!(weave.keys_overlap(colliding.nodes().keys().copied()));
+    assert!(!weave.keys_overlap(other.nodes().keys().copied()));
+    assert!(!weave.keys_overlap([].into_iter()));
+    let before = weave.clone();
+    assert!(!weave.graft(colliding, &0));
+    assert!(!weave.graft(other.clone(), &5));
+    assert_same_weave(&weave, &before);
+
+    assert!(weave.graft(other, &1));
+    assert_eq!(weave.len(), 5);
+    assert!(weave.roots().iter().eq([&0]));
+    assert!(weave.get_node(&1).unwrap().to.iter().eq([&10, &11]));
+    assert!(weave.get_node(&10).unwrap().from.iter().eq([&1]));
+    assert!(weave.get_node(&12).unwrap().from.iter().eq([&10, &11]));
+    assert!(weave.bookmarks().iter().eq([&1, &11]));
+    assert!(weave.active_thread_vec() == vec![1, 0]);
+    assert!(!weave.contains_active(&12));
+}
+
+#[test]
+fn remap_keys() {
+    let node = |id: u32, from: Vec<u32>, active: bool| IndependentNode {
+        id,
+        from: IndexSet::from_iter(from),
+        to: IndexSet::default(),
+        active,
+        bookmarked: id % 2 == 1,
+        contents: WeaveContent {
+            length: 1,
+            content_seed: id,
+        },
+        style: None,
+    };
+    let mut weave: IndependentWeave<u32, WeaveContent, u32, RandomState> =
+        IndependentWeave::from_nodes(
+            [
+                node(0, vec![], true),
+                node(1, vec![], false),
+                node(2, vec![0], false),
+                node(3, vec![0, 1], true),
+                node(4, vec![3], true),
+            ],
+            9,
+        )
+        .unwrap();
+
+    let remapped = weave.clone().remap_keys(|id| u64::from(id) + 100);
+    assert_eq!(remapped.len(), 5);
+    assert_eq!(remapped.metadata, 9);
+    assert!(remapped.roots().iter().eq([&100, &101]));
+    assert!(
+        remapped
+            .get_node(&103)
+            .unwrap()
+            .from
+            .iter()
+            .eq([&100, &101])
+    );
+    assert!(remapped.get_node(&100).unwrap().to.iter().eq([&102, &103]));
+    assert!(remapped.bookmarks().iter().eq([&101, &103]));
+    assert!(remapped.active_thread_vec() == vec![104, 103, 100]);
+    assert_eq!(remapped.get_node(&104).unwrap().contents.content_seed, 4);
+
+    let restored = remapped.remap_keys(|id| (id - 100) as u32);
+    assert_same_weave(&restored, &weave);
+
+    let overlapping = weave.clone();
+    assert!(weave.keys_overlap(overlapping.nodes().keys().copied()));
+    assert!(weave.graft(overlapping.remap_keys(|id| id + 5), &2));
+    assert_eq!(weave.len(), 10);
+    assert!(weave.get_node(&2).unwrap().to.iter().eq([&5, &6]));
+}
+
+#[test]
+fn sort_by_recency() {
+    let node = |id: u32, from: Vec<u32>, length: u32, created: u32| IndependentNode {
+        id,
+        from: IndexSet::from_iter(from),
+        to: IndexSet::default(),
+        active: false,
+        bookmarked: false,
+        contents: WeaveContent {
+            length,
+            content_seed: created,
+        },
+        style: None,
+    };
+    let mut weave: IndependentWeave<u32, WeaveContent, u32, RandomState> =
+        IndependentWeave::from_nodes(
+            [
+                node(0, vec![], 1, 5),
+                node(1, vec![], 0, 0),
+                node(2, vec![], 1, 9),
+                node(3, vec![0], 1, 1),
+                node(4, vec![0], 0, 0),
+                node(5, vec![0], 1, 3),
+                node(6, vec![0], 1, 2),
+            ],
+            0,
+        )
+        .unwrap();
+
+    assert!(weave.sort_children_by_recency(&0));
+    assert!(weave.get_node(&0).unwrap().to.iter().eq([&5, &6, &3, &4]));
+    assert!(!weave.sort_children_by_recency(&7));
+
+    weave.sort_roots_by_recency();
+    assert!(weave.roots().iter().eq([&2, &0, &1]));
+}
+
 /*
 #[test]
 fn transition_set() {
@@ -685,6 +3056,12 @@ fn transition_set() {
     };
     for item in items {
         state = WeaveWrapper::apply(state, &vec![], item);
+        let mut a: Vec<_> = state.weave.active().iter().copied().collect();
+        a.sort();
+        eprintln!("ACTIVE {:?}", a);
+        let mut n: Vec<_> = state.weave.nodes().values().map(|n| (n.id, n.from.clone(), n.to.clone())).collect();
+        n.sort_by_key(|x| x.0);
+        eprintln!("NODES {:?}", n);
     }
 }
 */
@@ -120,10 +120,24 @@ enum WeaveTransition {
     SortBookmarksById {
         sort_seed: u32,
     },
+    MoveChildToIndex {
+        id_seed: u32,
+        index: u8,
+    },
+    MoveRootToIndex {
+        id_seed: u32,
+        index: u8,
+    },
     GetContentsMut {
         id_seed: u32,
         content_seed: u32,
     },
+    WithNodeMut {
+        id_seed: u32,
+        content_seed: u32,
+        active: bool,
+        bookmarked: bool,
+    },
     Update,
     Commit,
     CommitAndRevert,
@@ -292,6 +306,40 @@ impl StateMachineTest for WeaveWrapper {
                     hash_value(*a as u64 + sort_seed).cmp(&hash_value(*b as u64 + sort_seed))
                 });
             }
+            WeaveTransition::MoveChildToIndex { id_seed, index } => {
+                let child = map_id(id_seed);
+                let parent = state.weave.get_node(&child).and_then(|node| node.from);
+
+                if let Some(parent) = parent {
+                    assert!(
+                        state
+                            .weave
+                            .move_child_to_index(&parent, &child, index as usize)
+                    );
+                    let siblings = &state.weave.get_node(&parent).unwrap().to;
+                    assert_eq!(
+                        siblings.get_index_of(&child),
+                        Some((index as usize).min(siblings.len() - 1))
+                    );
+                } else {
+                    assert!(
+                        !state
+                            .weave
+                            .move_child_to_index(&child, &child, index as usize)
+                    );
+                }
+            }
+            WeaveTransition::MoveRootToIndex { id_seed, index } => {
+                let id = map_id(id_seed);
+                let moved = state.weave.move_root_to_index(&id, index as usize);
+                assert_eq!(moved, state.weave.roots().contains(&id));
+                if moved {
+                    assert_eq!(
+                        state.weave.roots().get_index_of(&id),
+                        Some((index as usize).min(state.weave.roots().len() - 1))
+                    );
+                }
+            }
             WeaveTransition::GetContentsMut {
                 id_seed,
                 content_seed,
@@ -300,6 +348,18 @@ impl StateMachineTest for WeaveWrapper {
                     .weave
                     .get_contents_mut(&map_id(id_seed), |c| c.length = content_seed % 64);
             }
+            WeaveTransition::WithNodeMut {
+                id_seed,
+                content_seed,
+                active,
+                bookmarked,
+            } => {
+                state.weave.with_node_mut(&map_id(id_seed), |node| {
+                    node.active = active;
+                    node.bookmarked = bookmarked;
+                    node.contents.length = content_seed % 64;
+                });
+            }
             WeaveTransition::Update => {
                 state.weave.update(|_doc| {}).unwrap();
             }
@@ -0,0 +1,104 @@
+#![cfg(feature = "rkyv")]
+
+use rkyv::rancor::{Error, Strategy};
+use universal_weave::versioning::{ChecksumError, VersionedBytes};
+
+const FORMAT_IDENTIFIER: [u8; 24] = *b"UniversalWeaveTestFormat";
+
+fn write_checked(versioned: &VersionedBytes) -> Vec<u8> {
+    let mut output = Vec::with_capacity(versioned.checked_output_length());
+    versioned
+        .write_checked(Strategy::<_, Error>::wrap(&mut output))
+        .unwrap();
+    output
+}
+
+#[test]
+fn checksum() {
+    let versioned = VersionedBytes {
+        format_identifier: FORMAT_IDENTIFIER,
+        version: 1,
+        data: b"123456789",
+    };
+
+    assert_eq!(versioned.checksum(), 0xCBF43926);
+    assert_eq!(
+        VersionedBytes {
+            data: &[],
+            ..versioned
+        }
+        .checksum(),
+        0
+    );
+}
+
+#[test]
+fn checked_round_trip() {
+    let data: Vec<u8> = (0..=255).collect();
+    let versioned = VersionedBytes {
+        format_identifier: FORMAT_IDENTIFIER,
+        version: 3,
+        data: &data,
+    };
+    let mut bytes = write_checked(&versioned);
+
+    assert_eq!(bytes.len(), versioned.checked_output_length());
+    assert_eq!(
+        VersionedBytes::try_from_bytes_checked(&bytes, FORMAT_IDENTIFIER),
+        Ok(versioned)
+    );
+    assert_eq!(
+        VersionedBytes::try_from_bytes_checked(&bytes, *b"AnotherUniversalWeaveFmt"),
+        Err(ChecksumError::InvalidHeader)
+    );
+    assert_eq!(
+        VersionedBytes::try_from_bytes_checked(&bytes[..40], FORMAT_IDENTIFIER),
+        Err(ChecksumError::InvalidHeader)
+    );
+
+    assert_eq!(
+        VersionedBytes::try_from_bytes(&bytes, FORMAT_IDENTIFIER),
+        None
+    );
+
+    bytes[100] ^= 0b100;
+
+    assert_eq!(
+        VersionedBytes::try_from_bytes_checked(&bytes, FORMAT_IDENTIFIER),
+        Err(ChecksumError::Mismatch)
+    );
+}
+
+#[test]
+fn unchecked_read_as_checked() {
+    let data: Vec<u8> = (0..=255).collect();
+    let versioned = VersionedBytes {
+        format_identifier: FORMAT_IDENTIFIER,
+        version: 3,
+        data: &data,
+    };
+    let mut bytes = Vec::with_capacity(versioned.output_length());
+    versioned
+        .write(Strategy::<_, Error>::wrap(&mut bytes))
+        .unwrap();
+
+    assert_eq!(
+        VersionedBytes::try_from_bytes(&bytes, FORMAT_IDENTIFIER),
+        Some(versioned)
+    );
+    assert_eq!(
+        VersionedBytes::try_from_bytes_checked(&bytes, FORMAT_IDENTIFIER),
+        Err(ChecksumError::InvalidHeader)
+    );
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic]
+fn checked_version_out_of_range() {
+    write_checked(&VersionedBytes {
+        format_identifier: FORMAT_IDENTIFIER,
+        version: 1 << 63,
+        data: &[],
+    });
+}